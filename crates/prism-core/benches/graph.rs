@@ -0,0 +1,64 @@
+//! 端口连接查询：端点索引与逐条扫描的对比
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use prism_core::domain::{Connection, DataType, Node, NodeGraph, Port};
+
+/// 构造 `n` 个节点的分层图，每个节点连向后面最多 `fan_out` 个节点
+fn layered_graph(n: usize, fan_out: usize) -> NodeGraph {
+    let mut graph = NodeGraph::new();
+    for i in 0..n {
+        graph
+            .add_node(
+                Node::new(format!("n{i}"), "test")
+                    .with_input(Port::new("in", "in", DataType::Any).multiple())
+                    .with_output(Port::new("out", "out", DataType::Any)),
+            )
+            .unwrap();
+    }
+    for i in 0..n {
+        for j in (i + 1)..n.min(i + 1 + fan_out) {
+            graph
+                .add_connection(Connection::new(
+                    format!("n{i}"),
+                    "out",
+                    format!("n{j}"),
+                    "in",
+                ))
+                .unwrap();
+        }
+    }
+    graph
+}
+
+fn port_lookup(c: &mut Criterion) {
+    let graph = layered_graph(2_000, 4);
+    let queries: Vec<(String, String)> = (0..graph.nodes.len())
+        .step_by(10)
+        .map(|i| (format!("n{i}"), "in".to_string()))
+        .collect();
+
+    let mut group = c.benchmark_group("port_connection_count");
+    group.bench_function("naive_scan", |b| {
+        b.iter(|| {
+            queries
+                .iter()
+                .map(|(node, port)| black_box(graph.input_connection_count(node, port)))
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("endpoint_index", |b| {
+        b.iter(|| {
+            let index = graph.connections_by_endpoint();
+            queries
+                .iter()
+                .map(|key| black_box(index.get(key).map_or(0, Vec::len)))
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, port_lookup);
+criterion_main!(benches);
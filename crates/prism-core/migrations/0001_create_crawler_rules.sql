@@ -0,0 +1,12 @@
+-- 爬虫规则：完整规则以 JSON 保存，常用查询条件冗余为独立列
+CREATE TABLE IF NOT EXISTS crawler_rules (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    name       TEXT    NOT NULL,
+    media_type TEXT    NOT NULL,
+    enabled    INTEGER NOT NULL DEFAULT 1,
+    rule_json  TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_crawler_rules_name ON crawler_rules (name);
+CREATE INDEX IF NOT EXISTS idx_crawler_rules_media_type ON crawler_rules (media_type);
+CREATE INDEX IF NOT EXISTS idx_crawler_rules_enabled ON crawler_rules (enabled);
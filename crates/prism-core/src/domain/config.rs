@@ -0,0 +1,457 @@
+//! 规则的全局与流程级配置
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 规则级全局配置，流程级配置在其基础上覆盖
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct GlobalConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<ConcurrencyConfig>,
+    /// 运行前需要预加载持久化 Cookie 的域名
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cookie_domains: Vec<String>,
+}
+
+/// HTTP 请求配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct HttpConfig {
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 请求超时（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyConfig>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+impl HttpConfig {
+    /// 以 `self` 覆盖 `base` 得到合并后的配置
+    ///
+    /// 两侧请求头先经 [`HttpConfig::normalize_headers`] 规范化再取并集，同名时以
+    /// `self` 为准；超时、User-Agent 与代理在 `self` 中为 `Some` 时覆盖；重试策略在
+    /// `self` 中不是默认值时覆盖。
+    pub fn merge_over(&self, base: &HttpConfig) -> HttpConfig {
+        let mut headers = normalized_headers(&base.headers);
+        headers.extend(normalized_headers(&self.headers));
+        HttpConfig {
+            headers,
+            timeout_ms: self.timeout_ms.or(base.timeout_ms),
+            user_agent: self.user_agent.clone().or_else(|| base.user_agent.clone()),
+            proxy: self.proxy.clone().or_else(|| base.proxy.clone()),
+            retry: if self.retry == RetryPolicy::default() {
+                base.retry.clone()
+            } else {
+                self.retry.clone()
+            },
+        }
+    }
+
+    /// 把请求头名称规范为首字母大写的形式（如 `content-type` → `Content-Type`）
+    ///
+    /// 忽略大小写后同名的请求头合并为一项。这里有意不采用“后写的生效”：`headers`
+    /// 是 `HashMap`，规则文件中的书写顺序在反序列化后已经丢失，因此冲突时按原始名称
+    /// 的字节序处理，排在后面的值生效（大写字母排在小写之前，如 `X-TOKEN` <
+    /// `X-Token` < `x-token`）。结果只取决于名称本身，每次加载都相同。
+    pub fn normalize_headers(&mut self) {
+        self.headers = normalized_headers(&self.headers);
+    }
+}
+
+pub(crate) fn normalized_headers(headers: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut entries: Vec<(&String, &String)> = headers.iter().collect();
+    entries.sort_unstable();
+    entries
+        .into_iter()
+        .map(|(name, value)| (canonical_header_name(name), value.clone()))
+        .collect()
+}
+
+/// 每个以 `-` 分隔的单词首字母大写、其余小写
+fn canonical_header_name(name: &str) -> String {
+    let mut canonical = String::with_capacity(name.len());
+    let mut word_start = true;
+    for c in name.trim().chars() {
+        canonical.push(if word_start {
+            c.to_ascii_uppercase()
+        } else {
+            c.to_ascii_lowercase()
+        });
+        word_start = c == '-';
+    }
+    canonical
+}
+
+/// 流程实际使用的 HTTP 配置：流程级配置覆盖全局配置，两者都未设置时为默认值
+///
+/// 结果中的请求头名称总是经过 [`HttpConfig::normalize_headers`] 规范化。
+pub fn resolve_http(global: Option<&HttpConfig>, flow: Option<&HttpConfig>) -> HttpConfig {
+    match (global, flow) {
+        (Some(global), Some(flow)) => flow.merge_over(global),
+        (Some(config), None) | (None, Some(config)) => {
+            let mut config = config.clone();
+            config.normalize_headers();
+            config
+        }
+        (None, None) => HttpConfig::default(),
+    }
+}
+
+/// 代理配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ProxyConfig {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// 失败重试策略
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// 基础重试间隔（毫秒）
+    pub delay_ms: u64,
+    pub exponential_backoff: bool,
+    /// 触发重试的 HTTP 状态码
+    pub retry_on_status: Vec<u16>,
+    /// 是否对重试间隔做 ±20% 的随机抖动，避免大量请求同时重试
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            delay_ms: 1000,
+            exponential_backoff: false,
+            retry_on_status: vec![429, 500, 502, 503, 504],
+            jitter: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 单次重试间隔的上限（毫秒），抖动前生效
+    pub const MAX_DELAY_MS: u64 = 5 * 60 * 1000;
+
+    /// 第 `attempt` 次重试（从 1 开始）前的等待时间
+    ///
+    /// 线性模式下为 `delay_ms`；指数退避时为 `delay_ms * 2^(attempt-1)`。两种模式
+    /// 都不超过 [`RetryPolicy::MAX_DELAY_MS`]，`delay_ms` 来自规则文件，过大的值
+    /// 同样被截断。开启 `jitter` 时在此基础上随机浮动 ±20%。
+    pub fn delay_for_attempt(&self, attempt: usize) -> Duration {
+        let base = self.base_delay_ms(attempt).min(Self::MAX_DELAY_MS);
+        let millis = if self.jitter && base > 0 {
+            let spread = base / 5;
+            rand::rng().random_range(base.saturating_sub(spread)..=base.saturating_add(spread))
+        } else {
+            base
+        };
+        Duration::from_millis(millis)
+    }
+
+    /// 响应状态码是否应当触发重试
+    pub fn should_retry_status(&self, status: u16) -> bool {
+        self.retry_on_status.contains(&status)
+    }
+
+    fn base_delay_ms(&self, attempt: usize) -> u64 {
+        if !self.exponential_backoff {
+            return self.delay_ms;
+        }
+        let exponent = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        let factor = 2u64.checked_pow(exponent).unwrap_or(u64::MAX);
+        self.delay_ms.saturating_mul(factor)
+    }
+}
+
+/// 并发与请求节流配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ConcurrencyConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+    /// 两次请求之间的固定间隔（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+    /// 两次请求之间的随机间隔范围（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub random_delay_ms: Option<(u64, u64)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+}
+
+impl ConcurrencyConfig {
+    /// 以 `self` 覆盖 `base` 得到合并后的配置：`self` 中为 `Some` 的字段覆盖，
+    /// `None` 的字段沿用 `base`
+    ///
+    /// `random_delay_ms` 作为整体覆盖，不会把两侧的上下界拼在一起。
+    pub fn merge_over(&self, base: &ConcurrencyConfig) -> ConcurrencyConfig {
+        ConcurrencyConfig {
+            max_concurrent: self.max_concurrent.or(base.max_concurrent),
+            delay_ms: self.delay_ms.or(base.delay_ms),
+            random_delay_ms: self.random_delay_ms.or(base.random_delay_ms),
+            retry: self.retry.clone().or_else(|| base.retry.clone()),
+        }
+    }
+
+    /// 两次请求之间应等待的时长
+    ///
+    /// 设置了 `random_delay_ms` 时在闭区间 `[min, max]` 内均匀取值，上下界写反时
+    /// 自动交换；否则返回 `delay_ms`，两者都未设置时返回 `None`。
+    pub fn sample_delay(&self) -> Option<Duration> {
+        let millis = match self.random_delay_ms {
+            Some((a, b)) => rand::rng().random_range(a.min(b)..=a.max(b)),
+            None => self.delay_ms?,
+        };
+        Some(Duration::from_millis(millis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sample_delay_stays_within_random_range() {
+        let mut config = ConcurrencyConfig {
+            delay_ms: Some(50),
+            random_delay_ms: Some((300, 100)),
+            ..ConcurrencyConfig::default()
+        };
+        for _ in 0..200 {
+            let delay = config.sample_delay().unwrap();
+            assert!(
+                (Duration::from_millis(100)..=Duration::from_millis(300)).contains(&delay),
+                "{delay:?}"
+            );
+        }
+
+        config.random_delay_ms = None;
+        assert_eq!(config.sample_delay(), Some(Duration::from_millis(50)));
+        assert_eq!(ConcurrencyConfig::default().sample_delay(), None);
+    }
+
+    #[test]
+    fn retry_policy_fills_missing_fields() {
+        let policy: RetryPolicy = serde_json::from_value(json!({ "max_retries": 5 })).unwrap();
+        assert_eq!(policy.max_retries, 5);
+        assert_eq!(policy.delay_ms, RetryPolicy::default().delay_ms);
+    }
+
+    #[test]
+    fn retry_delay_linear_and_exponential() {
+        let linear = RetryPolicy::default();
+        let delays: Vec<u64> = (1..=3)
+            .map(|n| linear.delay_for_attempt(n).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, vec![1000, 1000, 1000]);
+
+        let exponential = RetryPolicy {
+            exponential_backoff: true,
+            ..RetryPolicy::default()
+        };
+        let delays: Vec<u64> = (1..=3)
+            .map(|n| exponential.delay_for_attempt(n).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, vec![1000, 2000, 4000]);
+        assert_eq!(
+            exponential.delay_for_attempt(100),
+            Duration::from_millis(RetryPolicy::MAX_DELAY_MS)
+        );
+    }
+
+    #[test]
+    fn retry_jitter_stays_within_twenty_percent() {
+        let policy = RetryPolicy {
+            exponential_backoff: true,
+            jitter: true,
+            ..RetryPolicy::default()
+        };
+        for _ in 0..50 {
+            let millis = policy.delay_for_attempt(2).as_millis();
+            assert!((1600..=2400).contains(&millis), "{millis}");
+        }
+    }
+
+    #[test]
+    fn retry_delay_is_capped_for_huge_delay_ms() {
+        let max = Duration::from_millis(RetryPolicy::MAX_DELAY_MS);
+        for exponential_backoff in [false, true] {
+            let policy = RetryPolicy {
+                delay_ms: u64::MAX,
+                exponential_backoff,
+                ..RetryPolicy::default()
+            };
+            assert_eq!(policy.delay_for_attempt(3), max);
+
+            let jittered = RetryPolicy {
+                jitter: true,
+                ..policy
+            };
+            for _ in 0..20 {
+                let delay = jittered.delay_for_attempt(3);
+                assert!(delay >= max * 4 / 5 && delay <= max * 6 / 5, "{delay:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn should_retry_status_checks_configured_codes() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry_status(503));
+        assert!(!policy.should_retry_status(404));
+    }
+
+    #[test]
+    fn http_merge_unions_headers_and_overrides_scalars() {
+        let global = HttpConfig {
+            headers: HashMap::from([
+                ("Accept".into(), "text/html".into()),
+                ("Referer".into(), "https://a.com".into()),
+            ]),
+            timeout_ms: Some(10_000),
+            user_agent: Some("global".into()),
+            retry: RetryPolicy {
+                max_retries: 5,
+                ..RetryPolicy::default()
+            },
+            ..HttpConfig::default()
+        };
+        let flow = HttpConfig {
+            headers: HashMap::from([
+                ("accept".into(), "application/json".into()),
+                ("X-Token".into(), "t".into()),
+            ]),
+            timeout_ms: Some(3_000),
+            ..HttpConfig::default()
+        };
+
+        let merged = resolve_http(Some(&global), Some(&flow));
+        assert_eq!(
+            merged.headers,
+            HashMap::from([
+                ("Accept".into(), "application/json".into()),
+                ("Referer".into(), "https://a.com".into()),
+                ("X-Token".into(), "t".into()),
+            ])
+        );
+        assert_eq!(merged.timeout_ms, Some(3_000));
+        assert_eq!(merged.user_agent.as_deref(), Some("global"));
+        assert_eq!(merged.retry.max_retries, 5);
+
+        let flow_retry = HttpConfig {
+            retry: RetryPolicy {
+                max_retries: 0,
+                ..RetryPolicy::default()
+            },
+            ..HttpConfig::default()
+        };
+        assert_eq!(flow_retry.merge_over(&global).retry.max_retries, 0);
+        let resolved = resolve_http(None, Some(&flow));
+        assert_eq!(resolved.headers["Accept"], "application/json");
+        assert_eq!(resolved.timeout_ms, flow.timeout_ms);
+        assert_eq!(resolve_http(None, None), HttpConfig::default());
+    }
+
+    #[test]
+    fn normalize_headers_collapses_case_variants() {
+        let mut config = HttpConfig {
+            headers: HashMap::from([
+                ("content-type".into(), "text/html".into()),
+                ("Content-Type".into(), "application/json".into()),
+                ("x-API-key".into(), "k".into()),
+            ]),
+            ..HttpConfig::default()
+        };
+        config.normalize_headers();
+        assert_eq!(
+            config.headers,
+            HashMap::from([
+                ("Content-Type".into(), "text/html".into()),
+                ("X-Api-Key".into(), "k".into()),
+            ])
+        );
+
+        let before = config.clone();
+        config.normalize_headers();
+        assert_eq!(config, before);
+    }
+
+    #[test]
+    fn header_collisions_resolve_by_byte_order_of_original_names() {
+        let names = ["x-token", "X-Token", "X-TOKEN"];
+        for rotation in 0..names.len() {
+            let mut headers = HashMap::new();
+            for i in 0..names.len() {
+                let name = names[(rotation + i) % names.len()];
+                headers.insert(name.to_string(), name.to_string());
+            }
+            let mut config = HttpConfig {
+                headers,
+                ..HttpConfig::default()
+            };
+            config.normalize_headers();
+            assert_eq!(
+                config.headers,
+                HashMap::from([("X-Token".into(), "x-token".into())])
+            );
+        }
+
+        // 跨层合并时仍以覆盖方为准，字节序只在同一层内部决定
+        let base = HttpConfig {
+            headers: HashMap::from([("x-token".into(), "base".into())]),
+            ..HttpConfig::default()
+        };
+        let flow = HttpConfig {
+            headers: HashMap::from([("X-TOKEN".into(), "flow".into())]),
+            ..HttpConfig::default()
+        };
+        assert_eq!(flow.merge_over(&base).headers["X-Token"], "flow");
+    }
+
+    #[test]
+    fn concurrency_merge_falls_through_to_base() {
+        let global = ConcurrencyConfig {
+            max_concurrent: Some(4),
+            delay_ms: Some(500),
+            random_delay_ms: Some((100, 300)),
+            retry: Some(RetryPolicy::default()),
+        };
+        let flow = ConcurrencyConfig {
+            delay_ms: Some(2_000),
+            ..ConcurrencyConfig::default()
+        };
+
+        let merged = flow.merge_over(&global);
+        assert_eq!(merged.max_concurrent, Some(4));
+        assert_eq!(merged.delay_ms, Some(2_000));
+        assert_eq!(merged.random_delay_ms, Some((100, 300)));
+        assert_eq!(merged.retry, Some(RetryPolicy::default()));
+
+        let flow = ConcurrencyConfig {
+            random_delay_ms: Some((0, 50)),
+            ..ConcurrencyConfig::default()
+        };
+        assert_eq!(flow.merge_over(&global).random_delay_ms, Some((0, 50)));
+    }
+
+    #[test]
+    fn global_config_deserializes_empty() {
+        let config: GlobalConfig = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(config, GlobalConfig::default());
+    }
+}
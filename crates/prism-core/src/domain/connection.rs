@@ -0,0 +1,254 @@
+//! 节点之间的连接
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+
+use super::error::ValidationError;
+use super::graph::NodeGraph;
+
+/// 从一个节点的输出端口指向另一个节点输入端口的有向边
+///
+/// 相等性与哈希只取决于两端的节点与端口，`label` 与 `meta` 只是附注：
+/// 端点相同而附注不同的两条连接仍视为重复。
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Connection {
+    pub from_node: String,
+    pub from_port: String,
+    pub to_node: String,
+    pub to_port: String,
+    /// 显示在连线上的说明，如“备用来源”
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// 编辑器使用的自由格式数据，如连线的路由点
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub meta: HashMap<String, Value>,
+}
+
+impl PartialEq for Connection {
+    fn eq(&self, other: &Self) -> bool {
+        self.endpoints() == other.endpoints()
+    }
+}
+
+impl Eq for Connection {}
+
+impl Hash for Connection {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.endpoints().hash(state);
+    }
+}
+
+impl Connection {
+    pub fn new(
+        from_node: impl Into<String>,
+        from_port: impl Into<String>,
+        to_node: impl Into<String>,
+        to_port: impl Into<String>,
+    ) -> Self {
+        Self {
+            from_node: from_node.into(),
+            from_port: from_port.into(),
+            to_node: to_node.into(),
+            to_port: to_port.into(),
+            label: None,
+            meta: HashMap::new(),
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    fn endpoints(&self) -> (&str, &str, &str, &str) {
+        (
+            &self.from_node,
+            &self.from_port,
+            &self.to_node,
+            &self.to_port,
+        )
+    }
+
+    /// 连接的唯一标识，形如 `from_node.from_port->to_node.to_port`
+    pub fn key(&self) -> String {
+        format!(
+            "{}.{}->{}.{}",
+            self.from_node, self.from_port, self.to_node, self.to_port
+        )
+    }
+
+    /// 检查这条（尚未加入的）连接放入 `graph` 后是否合法，不修改图
+    ///
+    /// 依次检查两端节点与端口存在、数据类型在图配置的类型兼容规则下兼容、输入端口未超出连接数上限，
+    /// 以及加入后不会形成环。
+    /// 供编辑器在拖拽连线时做实时反馈，无需校验整张图。
+    pub fn validate_against(&self, graph: &NodeGraph) -> Result<(), ValidationError> {
+        let node = |id: &str| {
+            graph
+                .get_node(id)
+                .ok_or_else(|| ValidationError::NodeNotFound {
+                    node_id: id.to_string(),
+                })
+        };
+        let port_not_found = |node_id: &str, port_id: &str| ValidationError::PortNotFound {
+            node_id: node_id.to_string(),
+            port_id: port_id.to_string(),
+        };
+
+        let from_port = node(&self.from_node)?
+            .get_output(&self.from_port)
+            .ok_or_else(|| port_not_found(&self.from_node, &self.from_port))?;
+        let to_port = node(&self.to_node)?
+            .get_input(&self.to_port)
+            .ok_or_else(|| port_not_found(&self.to_node, &self.to_port))?;
+
+        if !graph
+            .config
+            .type_compatibility()
+            .is_compatible(from_port.data_type, to_port.data_type)
+        {
+            return Err(ValidationError::TypeMismatch {
+                from_node: self.from_node.clone(),
+                from_port: self.from_port.clone(),
+                from_type: from_port.data_type,
+                to_node: self.to_node.clone(),
+                to_port: self.to_port.clone(),
+                to_type: to_port.data_type,
+            });
+        }
+
+        let count = graph.input_connection_count(&self.to_node, &self.to_port);
+        if !graph.connections.contains(self)
+            && to_port
+                .connection_limit()
+                .is_some_and(|limit| count >= limit)
+        {
+            return Err(ValidationError::PortOverConnected {
+                node_id: self.to_node.clone(),
+                port_id: self.to_port.clone(),
+                count: count + 1,
+            });
+        }
+
+        if let Some(mut path) = path_between(graph, &self.to_node, &self.from_node) {
+            path.push(self.to_node.clone());
+            return Err(ValidationError::CycleDetected { path });
+        }
+        Ok(())
+    }
+}
+
+/// 沿现有连接从 `start` 到 `target` 的一条路径（含两端）
+fn path_between(graph: &NodeGraph, start: &str, target: &str) -> Option<Vec<String>> {
+    let mut parent: HashMap<String, String> = HashMap::new();
+    let mut visited: HashSet<&str> = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(id) = queue.pop_front() {
+        if id == target {
+            let mut path = vec![id.to_string()];
+            let mut current = id;
+            while let Some(prev) = parent.get(current) {
+                path.push(prev.clone());
+                current = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for c in graph.connections.iter().filter(|c| c.from_node == id) {
+            if visited.insert(c.to_node.as_str()) {
+                parent.insert(c.to_node.clone(), id.to_string());
+                queue.push_back(c.to_node.as_str());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::graph::test_support::chain_graph;
+    use crate::domain::{DataType, Node, Port};
+
+    #[test]
+    fn label_does_not_affect_identity() {
+        let plain = Connection::new("a", "out", "b", "in");
+        let labeled = plain.clone().with_label("备用来源");
+        assert_eq!(plain.key(), labeled.key());
+        assert_eq!(plain, labeled);
+        assert_eq!(HashSet::from([plain.clone(), labeled.clone()]).len(), 1);
+
+        let mut graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        graph.add_connection(labeled).unwrap();
+        assert_eq!(graph.connections.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            serde_json::json!({ "from_node": "a", "from_port": "out", "to_node": "b", "to_port": "in" })
+        );
+    }
+
+    #[test]
+    fn validate_against_accepts_compatible_connection() {
+        let mut graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        graph
+            .add_node(Node::new("d", "test").with_input(Port::new("in", "in", DataType::Any)))
+            .unwrap();
+        let conn = Connection::new("a", "out", "d", "in");
+        assert_eq!(conn.validate_against(&graph), Ok(()));
+        assert!(graph.connections.iter().all(|c| c != &conn));
+    }
+
+    #[test]
+    fn validate_against_reports_type_mismatch() {
+        let mut graph = NodeGraph::new();
+        graph
+            .add_node(Node::new("a", "test").with_output(Port::new("out", "out", DataType::Number)))
+            .unwrap();
+        graph
+            .add_node(Node::new("b", "test").with_input(Port::new("in", "in", DataType::Boolean)))
+            .unwrap();
+        let err = Connection::new("a", "out", "b", "in")
+            .validate_against(&graph)
+            .unwrap_err();
+        assert!(matches!(err, ValidationError::TypeMismatch { .. }));
+    }
+
+    #[test]
+    fn validate_against_detects_would_be_cycle() {
+        let mut graph = NodeGraph::new();
+        for id in ["a", "b"] {
+            graph
+                .add_node(
+                    Node::new(id, "test")
+                        .with_input(Port::new("in", "in", DataType::Any))
+                        .with_output(Port::new("out", "out", DataType::Any)),
+                )
+                .unwrap();
+        }
+        graph
+            .add_connection(Connection::new("a", "out", "b", "in"))
+            .unwrap();
+        let err = Connection::new("b", "out", "a", "in")
+            .validate_against(&graph)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::CycleDetected {
+                path: vec!["a".into(), "b".into(), "a".into()]
+            }
+        );
+    }
+
+    #[test]
+    fn validate_against_reports_missing_port() {
+        let graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        let err = Connection::new("a", "nope", "b", "in")
+            .validate_against(&graph)
+            .unwrap_err();
+        assert!(matches!(err, ValidationError::PortNotFound { .. }));
+    }
+}
@@ -0,0 +1,506 @@
+//! 领域错误类型
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use thiserror::Error;
+
+use super::types::DataType;
+
+/// 领域层统一结果类型
+pub type Result<T> = std::result::Result<T, DomainError>;
+
+/// 领域层错误
+#[derive(Debug, Error)]
+pub enum DomainError {
+    /// 节点图校验失败
+    #[error("校验失败: {0}")]
+    Validation(#[from] ValidationError),
+
+    /// 请求的资源不存在
+    #[error("未找到: {0}")]
+    NotFound(String),
+
+    /// 序列化或反序列化失败
+    #[error("序列化错误: {0}")]
+    Serialization(String),
+
+    /// 执行期错误
+    #[error("执行错误: {0}")]
+    Execution(String),
+
+    /// 持久化存储读写失败
+    #[error("存储错误: {0}")]
+    Storage(String),
+
+    /// 规则或流程结构不合法
+    #[error("规则无效: {0}")]
+    InvalidRule(String),
+}
+
+impl From<serde_json::Error> for DomainError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Serialization(err.to_string())
+    }
+}
+
+impl From<serde_yaml::Error> for DomainError {
+    fn from(err: serde_yaml::Error) -> Self {
+        Self::Serialization(err.to_string())
+    }
+}
+
+/// 节点图校验错误
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    /// 图中存在循环依赖，`path` 为构成环的节点序列
+    #[error("检测到循环依赖: {}", path.join(" -> "))]
+    CycleDetected { path: Vec<String> },
+
+    /// 引用了不存在的节点
+    #[error("节点不存在: {node_id}")]
+    NodeNotFound { node_id: String },
+
+    /// 节点 ID 重复
+    #[error("节点 ID 重复: {node_id}")]
+    DuplicateNodeId { node_id: String },
+
+    /// 引用了节点上不存在的端口
+    #[error("端口不存在: {node_id}.{port_id}")]
+    PortNotFound { node_id: String, port_id: String },
+
+    /// 必需的输入端口没有连接
+    #[error("必需端口未连接: {node_id}.{port_id}")]
+    PortNotConnected { node_id: String, port_id: String },
+
+    /// 节点配置不符合其类型声明的 JSON Schema，或节点类型未注册
+    #[error("节点 {node_id} ({node_type}) 的配置无效: {reason}")]
+    InvalidNodeConfig {
+        node_id: String,
+        node_type: String,
+        reason: String,
+    },
+
+    /// 输入端口的连接数超过其上限
+    #[error("输入端口连接过多: {node_id}.{port_id} 有 {count} 个连接")]
+    PortOverConnected {
+        node_id: String,
+        port_id: String,
+        count: usize,
+    },
+
+    /// 节点有输入端口，但无法沿连接从任何入口节点（没有输入端口的节点）到达
+    #[error("节点无法从任何入口到达: {node_id}")]
+    UnreachableNode { node_id: String },
+
+    /// 出口节点（没有输出端口的节点）沿连接反向找不到任何入口节点，永远不会产生数据
+    #[error("出口节点没有数据来源: {node_id}")]
+    ExitNotFed { node_id: String },
+
+    /// 连接两端的数据类型不兼容
+    #[error(
+        "类型不匹配: {from_node}.{from_port} ({from_type}) -> {to_node}.{to_port} ({to_type})"
+    )]
+    TypeMismatch {
+        from_node: String,
+        from_port: String,
+        from_type: DataType,
+        to_node: String,
+        to_port: String,
+        to_type: DataType,
+    },
+}
+
+impl ValidationError {
+    /// 稳定的错误代码，如 `cycle_detected`，前端据此匹配错误而不必解析消息
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::CycleDetected { .. } => "cycle_detected",
+            Self::NodeNotFound { .. } => "node_not_found",
+            Self::DuplicateNodeId { .. } => "duplicate_node_id",
+            Self::PortNotFound { .. } => "port_not_found",
+            Self::PortNotConnected { .. } => "port_not_connected",
+            Self::InvalidNodeConfig { .. } => "invalid_node_config",
+            Self::PortOverConnected { .. } => "port_over_connected",
+            Self::UnreachableNode { .. } => "unreachable_node",
+            Self::ExitNotFed { .. } => "exit_not_fed",
+            Self::TypeMismatch { .. } => "type_mismatch",
+        }
+    }
+
+    /// 该问题的默认严重程度：不可达节点与没有来源的出口只是警告，其余都会阻止执行
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnreachableNode { .. } | Self::ExitNotFed { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// 错误主要涉及的节点，供界面高亮
+    ///
+    /// 循环依赖取环上的第一个节点，类型不匹配取接收数据的目标节点。
+    pub fn node_id(&self) -> Option<&str> {
+        match self {
+            Self::CycleDetected { path } => path.first().map(String::as_str),
+            Self::NodeNotFound { node_id }
+            | Self::DuplicateNodeId { node_id }
+            | Self::PortNotFound { node_id, .. }
+            | Self::PortNotConnected { node_id, .. }
+            | Self::InvalidNodeConfig { node_id, .. }
+            | Self::PortOverConnected { node_id, .. }
+            | Self::UnreachableNode { node_id }
+            | Self::ExitNotFed { node_id } => Some(node_id),
+            Self::TypeMismatch { to_node, .. } => Some(to_node),
+        }
+    }
+
+    /// 错误涉及的节点、端口等字段，供前端定位与本地化
+    fn details(&self) -> Value {
+        match self {
+            Self::CycleDetected { path } => json!({ "path": path }),
+            Self::NodeNotFound { node_id }
+            | Self::DuplicateNodeId { node_id }
+            | Self::UnreachableNode { node_id }
+            | Self::ExitNotFed { node_id } => json!({ "node_id": node_id }),
+            Self::PortNotFound { node_id, port_id }
+            | Self::PortNotConnected { node_id, port_id } => {
+                json!({ "node_id": node_id, "port_id": port_id })
+            }
+            Self::InvalidNodeConfig {
+                node_id,
+                node_type,
+                reason,
+            } => json!({ "node_id": node_id, "node_type": node_type, "reason": reason }),
+            Self::PortOverConnected {
+                node_id,
+                port_id,
+                count,
+            } => json!({ "node_id": node_id, "port_id": port_id, "count": count }),
+            Self::TypeMismatch {
+                from_node,
+                from_port,
+                from_type,
+                to_node,
+                to_port,
+                to_type,
+            } => json!({
+                "from_node": from_node,
+                "from_port": from_port,
+                "from_type": from_type,
+                "to_node": to_node,
+                "to_port": to_port,
+                "to_type": to_type,
+            }),
+        }
+    }
+}
+
+/// 校验问题的严重程度
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 图无法执行
+    Error,
+    /// 可以执行，但很可能不是预期的行为
+    Warning,
+    /// 仅供参考
+    Info,
+}
+
+/// 一条带严重程度的校验结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub error: ValidationError,
+}
+
+impl Finding {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl From<ValidationError> for Finding {
+    /// 使用 [`ValidationError::severity`] 给出的默认严重程度
+    fn from(error: ValidationError) -> Self {
+        Self {
+            severity: error.severity(),
+            error,
+        }
+    }
+}
+
+/// 发送给前端的结构化错误
+///
+/// `code` 是稳定的错误代码，前端据此本地化与处理：校验错误为
+/// `validation.<类别>`（如 `validation.cycle_detected`），其余为 `not_found`、
+/// `serialization`、`execution`、`storage`、`invalid_rule`。校验错误的
+/// 节点、端口等字段放在 `details` 中。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ErrorPayload {
+    pub code: String,
+    /// 面向用户的错误描述
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl From<&ValidationError> for ErrorPayload {
+    fn from(err: &ValidationError) -> Self {
+        Self {
+            code: format!("validation.{}", err.code()),
+            message: err.to_string(),
+            details: Some(err.details()),
+        }
+    }
+}
+
+impl From<&DomainError> for ErrorPayload {
+    fn from(err: &DomainError) -> Self {
+        let code = match err {
+            DomainError::Validation(inner) => return inner.into(),
+            DomainError::NotFound(_) => "not_found",
+            DomainError::Serialization(_) => "serialization",
+            DomainError::Execution(_) => "execution",
+            DomainError::Storage(_) => "storage",
+            DomainError::InvalidRule(_) => "invalid_rule",
+        };
+        Self {
+            code: code.to_string(),
+            message: err.to_string(),
+            details: None,
+        }
+    }
+}
+
+impl From<ValidationError> for ErrorPayload {
+    fn from(err: ValidationError) -> Self {
+        (&err).into()
+    }
+}
+
+impl From<DomainError> for ErrorPayload {
+    fn from(err: DomainError) -> Self {
+        (&err).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(err: ValidationError) -> ErrorPayload {
+        ErrorPayload::from(&DomainError::Validation(err))
+    }
+
+    #[test]
+    fn validation_errors_map_to_codes_and_details() {
+        let node = || "n".to_string();
+        let port = || "p".to_string();
+        let cases = [
+            (
+                ValidationError::CycleDetected {
+                    path: vec!["a".into(), "b".into(), "a".into()],
+                },
+                "validation.cycle_detected",
+                json!({ "path": ["a", "b", "a"] }),
+            ),
+            (
+                ValidationError::NodeNotFound { node_id: node() },
+                "validation.node_not_found",
+                json!({ "node_id": "n" }),
+            ),
+            (
+                ValidationError::DuplicateNodeId { node_id: node() },
+                "validation.duplicate_node_id",
+                json!({ "node_id": "n" }),
+            ),
+            (
+                ValidationError::PortNotFound {
+                    node_id: node(),
+                    port_id: port(),
+                },
+                "validation.port_not_found",
+                json!({ "node_id": "n", "port_id": "p" }),
+            ),
+            (
+                ValidationError::PortNotConnected {
+                    node_id: node(),
+                    port_id: port(),
+                },
+                "validation.port_not_connected",
+                json!({ "node_id": "n", "port_id": "p" }),
+            ),
+            (
+                ValidationError::InvalidNodeConfig {
+                    node_id: node(),
+                    node_type: "http_request".into(),
+                    reason: "bad".into(),
+                },
+                "validation.invalid_node_config",
+                json!({ "node_id": "n", "node_type": "http_request", "reason": "bad" }),
+            ),
+            (
+                ValidationError::PortOverConnected {
+                    node_id: node(),
+                    port_id: port(),
+                    count: 2,
+                },
+                "validation.port_over_connected",
+                json!({ "node_id": "n", "port_id": "p", "count": 2 }),
+            ),
+            (
+                ValidationError::UnreachableNode { node_id: node() },
+                "validation.unreachable_node",
+                json!({ "node_id": "n" }),
+            ),
+            (
+                ValidationError::ExitNotFed { node_id: node() },
+                "validation.exit_not_fed",
+                json!({ "node_id": "n" }),
+            ),
+            (
+                ValidationError::TypeMismatch {
+                    from_node: "a".into(),
+                    from_port: "out".into(),
+                    from_type: DataType::Number,
+                    to_node: "b".into(),
+                    to_port: "in".into(),
+                    to_type: DataType::Boolean,
+                },
+                "validation.type_mismatch",
+                json!({
+                    "from_node": "a",
+                    "from_port": "out",
+                    "from_type": "number",
+                    "to_node": "b",
+                    "to_port": "in",
+                    "to_type": "boolean",
+                }),
+            ),
+        ];
+        for (err, code, details) in cases {
+            let message = err.to_string();
+            let payload = payload(err);
+            assert_eq!(payload.code, code);
+            assert_eq!(payload.message, message);
+            assert_eq!(payload.details, Some(details));
+        }
+    }
+
+    #[test]
+    fn validation_error_codes_and_node_ids() {
+        let cases = [
+            (
+                ValidationError::CycleDetected {
+                    path: vec!["a".into(), "b".into(), "a".into()],
+                },
+                "cycle_detected",
+                Some("a"),
+            ),
+            (
+                ValidationError::CycleDetected { path: Vec::new() },
+                "cycle_detected",
+                None,
+            ),
+            (
+                ValidationError::NodeNotFound {
+                    node_id: "n".into(),
+                },
+                "node_not_found",
+                Some("n"),
+            ),
+            (
+                ValidationError::DuplicateNodeId {
+                    node_id: "n".into(),
+                },
+                "duplicate_node_id",
+                Some("n"),
+            ),
+            (
+                ValidationError::PortNotFound {
+                    node_id: "n".into(),
+                    port_id: "p".into(),
+                },
+                "port_not_found",
+                Some("n"),
+            ),
+            (
+                ValidationError::PortNotConnected {
+                    node_id: "n".into(),
+                    port_id: "p".into(),
+                },
+                "port_not_connected",
+                Some("n"),
+            ),
+            (
+                ValidationError::InvalidNodeConfig {
+                    node_id: "n".into(),
+                    node_type: "t".into(),
+                    reason: "r".into(),
+                },
+                "invalid_node_config",
+                Some("n"),
+            ),
+            (
+                ValidationError::PortOverConnected {
+                    node_id: "n".into(),
+                    port_id: "p".into(),
+                    count: 3,
+                },
+                "port_over_connected",
+                Some("n"),
+            ),
+            (
+                ValidationError::UnreachableNode {
+                    node_id: "n".into(),
+                },
+                "unreachable_node",
+                Some("n"),
+            ),
+            (
+                ValidationError::ExitNotFed {
+                    node_id: "n".into(),
+                },
+                "exit_not_fed",
+                Some("n"),
+            ),
+            (
+                ValidationError::TypeMismatch {
+                    from_node: "a".into(),
+                    from_port: "out".into(),
+                    from_type: DataType::String,
+                    to_node: "b".into(),
+                    to_port: "in".into(),
+                    to_type: DataType::Number,
+                },
+                "type_mismatch",
+                Some("b"),
+            ),
+        ];
+        for (err, code, node_id) in cases {
+            assert_eq!(err.code(), code);
+            assert_eq!(err.node_id(), node_id);
+            let expected = if matches!(code, "unreachable_node" | "exit_not_fed") {
+                Severity::Warning
+            } else {
+                Severity::Error
+            };
+            assert_eq!(Finding::from(err).severity, expected);
+        }
+    }
+
+    #[test]
+    fn other_domain_errors_have_no_details() {
+        let payload = ErrorPayload::from(&DomainError::NotFound("rule 1".into()));
+        assert_eq!(payload.code, "not_found");
+        assert_eq!(payload.message, "未找到: rule 1");
+        assert_eq!(payload.details, None);
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({ "code": "not_found", "message": "未找到: rule 1" })
+        );
+    }
+}
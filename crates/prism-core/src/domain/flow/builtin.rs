@@ -0,0 +1,286 @@
+//! 内置流程模板
+//!
+//! 为每种媒体类型提供一组入门模板，新用户可以直接挑选而不必从零连线。
+
+use serde_json::{json, Value};
+
+use super::template::{FlowParameter, FlowTemplate, ParamType};
+use super::FlowType;
+use crate::domain::connection::Connection;
+use crate::domain::graph::NodeGraph;
+use crate::domain::media::MediaType;
+use crate::domain::node::{Node, Port};
+use crate::domain::types::DataType;
+
+impl FlowTemplate {
+    /// 指定媒体类型的内置模板：分页列表发现、搜索、详情页提取与内容提取
+    ///
+    /// 所有参数都带有默认值，因此模板可以直接以空参数实例化。
+    pub fn builtin_templates(media_type: MediaType) -> Vec<FlowTemplate> {
+        vec![
+            paginated_list(&media_type),
+            search(&media_type),
+            detail_page(&media_type),
+            content(&media_type),
+        ]
+    }
+}
+
+fn base_url_param() -> FlowParameter {
+    FlowParameter::new("base_url", ParamType::String)
+        .with_description("站点根地址")
+        .with_default(json!("https://example.com"))
+}
+
+fn http_request(id: &str, url: &str) -> Node {
+    Node::new(id, "http_request")
+        .with_output(Port::new("body", "响应内容", DataType::String))
+        .with_output(Port::new("status", "状态码", DataType::Number))
+        .with_config(json!({ "url": url, "method": "GET" }))
+}
+
+fn css_selector(id: &str, selector: &str, attribute: &str) -> Node {
+    Node::new(id, "css_selector")
+        .with_input(Port::new("html", "HTML", DataType::String))
+        .with_output(Port::new("result", "结果", DataType::Array))
+        .with_config(json!({ "selector": selector, "attribute": attribute }))
+}
+
+fn regex_extract(id: &str, pattern: &str) -> Node {
+    Node::new(id, "regex_extract")
+        .with_input(Port::new("text", "文本", DataType::String))
+        .with_output(Port::new("matches", "匹配结果", DataType::Array))
+        .with_config(json!({ "pattern": pattern, "group": 1 }))
+}
+
+fn set_variable(id: &str, name: &str) -> Node {
+    Node::new(id, "set_variable")
+        .with_input(Port::new("value", "值", DataType::Any))
+        .with_config(json!({ "name": name }))
+}
+
+/// 按 `(节点, 输出端口, 节点, 输入端口)` 列表组装模板图
+fn build_graph(nodes: Vec<Node>, edges: &[(&str, &str, &str, &str)]) -> NodeGraph {
+    let mut graph = NodeGraph::new();
+    for node in nodes {
+        graph.add_node(node).expect("内置模板节点 ID 不应重复");
+    }
+    for (from, from_port, to, to_port) in edges {
+        graph
+            .add_connection(Connection::new(*from, *from_port, *to, *to_port))
+            .expect("内置模板连接应引用已存在的端口");
+    }
+    graph
+}
+
+fn template(
+    media_type: &MediaType,
+    flow_type: FlowType,
+    key: &str,
+    name: &str,
+    description: &str,
+    parameters: Vec<FlowParameter>,
+    graph: NodeGraph,
+) -> FlowTemplate {
+    FlowTemplate {
+        id: format!("{}.{}", media_type.as_str(), key),
+        name: name.to_string(),
+        description: description.to_string(),
+        media_type: media_type.clone(),
+        flow_type,
+        parameters,
+        graph,
+    }
+}
+
+fn paginated_list(media_type: &MediaType) -> FlowTemplate {
+    let graph = build_graph(
+        vec![
+            http_request("fetch", "{{base_url}}{{list_path}}?page={{page}}"),
+            css_selector("items", "{{item_selector}}", "href"),
+            set_variable("output", "items"),
+        ],
+        &[
+            ("fetch", "body", "items", "html"),
+            ("items", "result", "output", "value"),
+        ],
+    );
+    template(
+        media_type,
+        FlowType::Discovery,
+        "paginated_list",
+        "分页列表",
+        "按页码抓取列表页并提取条目链接",
+        vec![
+            base_url_param(),
+            FlowParameter::new("list_path", ParamType::String).with_default(json!("/list")),
+            FlowParameter::new("page", ParamType::Number).with_default(json!(1)),
+            FlowParameter::new("item_selector", ParamType::String).with_default(json!(".item a")),
+        ],
+        graph,
+    )
+}
+
+fn search(media_type: &MediaType) -> FlowTemplate {
+    let graph = build_graph(
+        vec![
+            http_request("fetch", "{{base_url}}/search?q={{query}}"),
+            css_selector("results", "{{result_selector}}", "href"),
+            set_variable("output", "results"),
+        ],
+        &[
+            ("fetch", "body", "results", "html"),
+            ("results", "result", "output", "value"),
+        ],
+    );
+    template(
+        media_type,
+        FlowType::Search,
+        "search",
+        "关键词搜索",
+        "请求搜索页并提取结果链接",
+        vec![
+            base_url_param(),
+            FlowParameter::new("query", ParamType::String)
+                .with_description("搜索关键词")
+                .with_default(json!("")),
+            FlowParameter::new("result_selector", ParamType::String)
+                .with_default(json!(".result a")),
+        ],
+        graph,
+    )
+}
+
+fn detail_page(media_type: &MediaType) -> FlowTemplate {
+    let graph = build_graph(
+        vec![
+            http_request("fetch", "{{url}}"),
+            css_selector("title", "{{title_selector}}", "text"),
+            css_selector("cover", "{{cover_selector}}", "src"),
+            set_variable("title_output", "title"),
+            set_variable("cover_output", "cover"),
+        ],
+        &[
+            ("fetch", "body", "title", "html"),
+            ("fetch", "body", "cover", "html"),
+            ("title", "result", "title_output", "value"),
+            ("cover", "result", "cover_output", "value"),
+        ],
+    );
+    template(
+        media_type,
+        FlowType::Detail,
+        "detail_page",
+        "详情页提取",
+        "从详情页提取标题与封面",
+        vec![
+            FlowParameter::new("url", ParamType::String)
+                .with_description("详情页地址")
+                .with_default(json!("https://example.com/detail")),
+            FlowParameter::new("title_selector", ParamType::String).with_default(json!("h1")),
+            FlowParameter::new("cover_selector", ParamType::String)
+                .with_default(json!("img.cover")),
+        ],
+        graph,
+    )
+}
+
+fn content(media_type: &MediaType) -> FlowTemplate {
+    let (extract, output): (Node, &str) = match media_type {
+        MediaType::Video => (
+            regex_extract("extract", r#"["'](https?://[^"']+\.m3u8[^"']*)["']"#),
+            "play_url",
+        ),
+        MediaType::Music => (
+            regex_extract(
+                "extract",
+                r#"["'](https?://[^"']+\.(?:mp3|m4a|flac)[^"']*)["']"#,
+            ),
+            "audio_url",
+        ),
+        MediaType::Novel => (css_selector("extract", "#content", "text"), "text"),
+        MediaType::Comic | MediaType::Image => (
+            css_selector("extract", "{{image_selector}}", "src"),
+            "images",
+        ),
+        MediaType::General | MediaType::Other(_) => {
+            (css_selector("extract", "body", "text"), "content")
+        }
+    };
+    let input_port = extract.inputs[0].id.clone();
+    let output_port = extract.outputs[0].id.clone();
+
+    let mut parameters = vec![FlowParameter::new("url", ParamType::String)
+        .with_description("内容页地址")
+        .with_default(json!("https://example.com/content"))];
+    if matches!(media_type, MediaType::Comic | MediaType::Image) {
+        parameters.push(
+            FlowParameter::new("image_selector", ParamType::String)
+                .with_default(Value::String("img".into())),
+        );
+    }
+
+    let graph = build_graph(
+        vec![
+            http_request("fetch", "{{url}}"),
+            extract,
+            set_variable("output", output),
+        ],
+        &[
+            ("fetch", "body", "extract", &input_port),
+            ("extract", &output_port, "output", "value"),
+        ],
+    );
+    template(
+        media_type,
+        FlowType::Content,
+        "content",
+        "内容提取",
+        "从内容页提取正文或媒体地址",
+        parameters,
+        graph,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Map;
+
+    #[test]
+    fn builtin_templates_pass_port_validation() {
+        for media in MediaType::all() {
+            for template in FlowTemplate::builtin_templates(media.clone()) {
+                assert!(
+                    template.graph.validate_ports().is_ok(),
+                    "{} 的端口校验失败",
+                    template.id
+                );
+                assert_eq!(&template.media_type, media);
+            }
+        }
+    }
+
+    #[test]
+    fn video_templates_instantiate_with_defaults() {
+        let templates = FlowTemplate::builtin_templates(MediaType::Video);
+        assert!(!templates.is_empty());
+        for template in templates {
+            let flow = template.instantiate(&Map::new()).unwrap();
+            flow.validate().unwrap();
+            for node in flow.graph.nodes.values() {
+                let config = node.config.to_string();
+                assert!(!config.contains("{{"), "{} 仍有未替换的占位符", template.id);
+            }
+        }
+    }
+
+    #[test]
+    fn template_ids_are_unique_per_media() {
+        let templates = FlowTemplate::builtin_templates(MediaType::Novel);
+        let mut ids: Vec<_> = templates.iter().map(|t| t.id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), templates.len());
+    }
+}
@@ -0,0 +1,353 @@
+//! 规则的流程集合
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::{IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use specta::Type;
+
+use super::{Flow, FlowType};
+use crate::domain::error::{DomainError, Result};
+
+/// 流程之间的执行依赖：键对应的流程须在列出的流程之后执行（未定义的依赖忽略）
+const FLOW_DEPENDENCIES: &[(FlowType, &[FlowType])] = &[
+    (FlowType::Discovery, &[FlowType::Login]),
+    (FlowType::Search, &[FlowType::Login]),
+    (
+        FlowType::Pagination,
+        &[FlowType::Login, FlowType::Discovery, FlowType::Search],
+    ),
+    (FlowType::Detail, &[FlowType::Login]),
+    (FlowType::Content, &[FlowType::Login]),
+];
+
+fn dependencies(flow_type: FlowType) -> &'static [FlowType] {
+    FLOW_DEPENDENCIES
+        .iter()
+        .find(|(t, _)| *t == flow_type)
+        .map_or(&[], |(_, deps)| deps)
+}
+
+/// 一条规则包含的各类流程，每种类型至多一个
+///
+/// 内部以流程类型为键保存，新增流程类型无需修改本结构；序列化形状仍是以
+/// [`FlowType::as_str`] 为字段名的对象，如 `{ "search": { ... } }`，只包含已定义的流程。
+/// 与按字段定义时一样，反序列化时跳过未知的流程名（记录一条警告），较新版本写入的
+/// 规则在旧版本中仍能加载。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+#[serde(transparent)]
+pub struct Flows {
+    flows: BTreeMap<FlowType, Flow>,
+}
+
+impl<'de> Deserialize<'de> for Flows {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_map(FlowsVisitor)
+    }
+}
+
+struct FlowsVisitor;
+
+impl<'de> Visitor<'de> for FlowsVisitor {
+    type Value = Flows;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("以流程类型为键的对象")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Flows, A::Error> {
+        let mut flows = Flows::new();
+        while let Some(key) = map.next_key::<String>()? {
+            match FlowType::all().iter().find(|t| t.as_str() == key) {
+                Some(&flow_type) => {
+                    flows.insert(flow_type, map.next_value()?);
+                }
+                None => {
+                    tracing::warn!(flow = %key, "跳过未知的流程类型");
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(flows)
+    }
+}
+
+impl Flows {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_login(self, flow: Flow) -> Self {
+        self.with(FlowType::Login, flow)
+    }
+
+    pub fn with_discovery(self, flow: Flow) -> Self {
+        self.with(FlowType::Discovery, flow)
+    }
+
+    pub fn with_search(self, flow: Flow) -> Self {
+        self.with(FlowType::Search, flow)
+    }
+
+    pub fn with_pagination(self, flow: Flow) -> Self {
+        self.with(FlowType::Pagination, flow)
+    }
+
+    pub fn with_detail(self, flow: Flow) -> Self {
+        self.with(FlowType::Detail, flow)
+    }
+
+    pub fn with_content(self, flow: Flow) -> Self {
+        self.with(FlowType::Content, flow)
+    }
+
+    /// 将 `flow` 放在 `flow_type` 的位置上
+    pub fn with(mut self, flow_type: FlowType, flow: Flow) -> Self {
+        self.insert(flow_type, flow);
+        self
+    }
+
+    /// 设置某类流程，返回被替换的旧流程
+    pub fn insert(&mut self, flow_type: FlowType, flow: Flow) -> Option<Flow> {
+        self.flows.insert(flow_type, flow)
+    }
+
+    /// 移除某类流程
+    pub fn remove(&mut self, flow_type: FlowType) -> Option<Flow> {
+        self.flows.remove(&flow_type)
+    }
+
+    pub fn get(&self, flow_type: FlowType) -> Option<&Flow> {
+        self.flows.get(&flow_type)
+    }
+
+    pub fn get_mut(&mut self, flow_type: FlowType) -> Option<&mut Flow> {
+        self.flows.get_mut(&flow_type)
+    }
+
+    /// 对每个流程图的连接排序
+    ///
+    /// 见 [`NodeGraph::sort_connections`](crate::domain::NodeGraph::sort_connections)。
+    pub fn sort_connections(&mut self) {
+        for flow in self.flows.values_mut() {
+            flow.graph.sort_connections();
+        }
+    }
+
+    /// 已定义的流程，按 [`FlowType::all`] 的顺序
+    pub fn defined_flows(&self) -> Vec<(FlowType, &Flow)> {
+        FlowType::all()
+            .iter()
+            .filter_map(|&t| self.get(t).map(|f| (t, f)))
+            .collect()
+    }
+
+    /// 已定义流程的执行顺序：每个流程排在其依赖之后（如登录总在最前），
+    /// 没有依赖关系的流程按 [`FlowType::all`] 的顺序排列
+    pub fn execution_order(&self) -> Vec<FlowType> {
+        let mut pending: Vec<FlowType> = self.defined_flows().into_iter().map(|(t, _)| t).collect();
+        let mut order = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            // 依赖表无环，每轮总能找到一个依赖均已排好的流程
+            let ready = pending
+                .iter()
+                .position(|t| dependencies(*t).iter().all(|dep| !pending.contains(dep)))
+                .expect("流程依赖表不应有环");
+            order.push(pending.remove(ready));
+        }
+        order
+    }
+
+    /// 校验必需流程均已定义，且每个流程都放在与其类型对应的位置，返回第一个问题
+    pub fn validate(&self) -> Result<()> {
+        match self.validation_errors().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// 与 [`Flows::validate`] 相同的检查，按 [`FlowType::all`] 的顺序列出所有问题：
+    /// 每个缺少的必需流程、每个类型声明不符的流程各一项
+    pub fn validation_errors(&self) -> Vec<DomainError> {
+        FlowType::all()
+            .iter()
+            .filter_map(|flow_type| match self.get(*flow_type) {
+                None if flow_type.is_required() => Some(DomainError::InvalidRule(format!(
+                    "缺少必需的{}流程",
+                    flow_type.display_name()
+                ))),
+                Some(flow) if flow.flow_type != *flow_type => {
+                    Some(DomainError::InvalidRule(format!(
+                        "{}流程的类型声明为{}",
+                        flow_type.display_name(),
+                        flow.flow_type.display_name()
+                    )))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::NodeGraph;
+
+    fn flow(flow_type: FlowType) -> Flow {
+        Flow::new(flow_type, NodeGraph::new())
+    }
+
+    fn required_flows() -> Flows {
+        Flows::new()
+            .with_search(flow(FlowType::Search))
+            .with_detail(flow(FlowType::Detail))
+            .with_content(flow(FlowType::Content))
+    }
+
+    #[test]
+    fn defined_flows_follow_flow_type_order() {
+        let flows = required_flows().with_login(flow(FlowType::Login));
+        let types: Vec<_> = flows.defined_flows().into_iter().map(|(t, _)| t).collect();
+        assert_eq!(
+            types,
+            vec![
+                FlowType::Login,
+                FlowType::Search,
+                FlowType::Detail,
+                FlowType::Content
+            ]
+        );
+    }
+
+    #[test]
+    fn execution_order_runs_login_first() {
+        let flows = required_flows()
+            .with_discovery(flow(FlowType::Discovery))
+            .with_login(flow(FlowType::Login));
+        assert_eq!(
+            flows.execution_order(),
+            vec![
+                FlowType::Login,
+                FlowType::Discovery,
+                FlowType::Search,
+                FlowType::Detail,
+                FlowType::Content
+            ]
+        );
+        assert_eq!(
+            Flows::new()
+                .with_pagination(flow(FlowType::Pagination))
+                .with_discovery(flow(FlowType::Discovery))
+                .execution_order(),
+            vec![FlowType::Discovery, FlowType::Pagination]
+        );
+    }
+
+    #[test]
+    fn dependency_table_is_acyclic_and_respected() {
+        let mut flows = Flows::new();
+        for &flow_type in FlowType::all() {
+            flows.insert(flow_type, flow(flow_type));
+        }
+        let order = flows.execution_order();
+        assert_eq!(order.len(), FlowType::all().len());
+        for (i, flow_type) in order.iter().enumerate() {
+            for dep in dependencies(*flow_type) {
+                assert!(order[..i].contains(dep), "{flow_type:?} 应在 {dep:?} 之后");
+            }
+        }
+    }
+
+    #[test]
+    fn validate_requires_required_flows() {
+        assert!(required_flows().validate().is_ok());
+        let missing = Flows::new().with_search(flow(FlowType::Search));
+        assert!(matches!(
+            missing.validate(),
+            Err(DomainError::InvalidRule(_))
+        ));
+    }
+
+    #[test]
+    fn validation_errors_lists_every_problem() {
+        assert!(required_flows().validation_errors().is_empty());
+        let flows = Flows::new().with_login(flow(FlowType::Search));
+        let errors: Vec<String> = flows
+            .validation_errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        assert!(errors[0].contains("登录"), "{errors:?}");
+    }
+
+    #[test]
+    fn pagination_flow_is_optional() {
+        let flows = required_flows().with_pagination(flow(FlowType::Pagination));
+        assert!(flows.validate().is_ok());
+        let types: Vec<_> = flows.defined_flows().into_iter().map(|(t, _)| t).collect();
+        assert_eq!(
+            types,
+            vec![
+                FlowType::Search,
+                FlowType::Pagination,
+                FlowType::Detail,
+                FlowType::Content
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_misplaced_flow() {
+        let flows = required_flows().with_login(flow(FlowType::Search));
+        assert!(flows.validate().is_err());
+    }
+
+    #[test]
+    fn serializes_only_defined_flows() {
+        let json = serde_json::to_value(Flows::new().with_search(flow(FlowType::Search))).unwrap();
+        let keys: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(keys, vec!["search"]);
+    }
+
+    #[test]
+    fn deserialize_skips_unknown_flow_types() {
+        let mut json = serde_json::to_value(required_flows()).unwrap();
+        json["checkin"] = serde_json::json!({ "not": "a flow" });
+        let flows: Flows = serde_json::from_value(json).unwrap();
+        assert_eq!(flows, required_flows());
+
+        let err = serde_json::from_value::<Flows>(serde_json::json!({ "search": 1 }));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn json_shape_uses_named_fields() {
+        let flows = required_flows().with_login(flow(FlowType::Login));
+        let json = serde_json::to_value(&flows).unwrap();
+        let keys: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(keys, vec!["content", "detail", "login", "search"]);
+
+        let back: Flows = serde_json::from_value(json).unwrap();
+        assert_eq!(back, flows);
+        assert_eq!(
+            back.get(FlowType::Login).map(|f| f.flow_type),
+            Some(FlowType::Login)
+        );
+    }
+
+    #[test]
+    fn insert_and_remove_replace_flows() {
+        let mut flows = Flows::new();
+        assert!(flows
+            .insert(FlowType::Search, flow(FlowType::Search))
+            .is_none());
+        assert!(flows
+            .insert(FlowType::Search, flow(FlowType::Search))
+            .is_some());
+        assert!(flows.remove(FlowType::Search).is_some());
+        assert!(flows.get(FlowType::Search).is_none());
+    }
+}
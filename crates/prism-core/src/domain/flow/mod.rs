@@ -0,0 +1,500 @@
+//! 流程
+//!
+//! 一条规则由若干流程组成（登录、发现、搜索、分页、详情、内容），每个流程持有一张
+//! [`NodeGraph`]。
+//!
+//! # 入口节点标签约定
+//!
+//! 不同流程类型期望不同形态的起点，例如搜索流程应从能接收搜索词的节点开始。
+//! 节点类型通过注册表中的标签声明自己可作为哪类流程的入口，标签格式为
+//! `<流程类型>-entry`（流程类型取 [`FlowType::as_str`]），如 `search-entry`、
+//! `login-entry`。一个节点类型可以带多个入口标签。见 [`Flow::validate_entry_for_type`]。
+
+mod builtin;
+mod flows;
+mod template;
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use specta::Type;
+
+pub use flows::Flows;
+pub use template::{FlowParameter, FlowTemplate, ParamType};
+
+use super::config::{ConcurrencyConfig, HttpConfig};
+use super::error::{DomainError, Result};
+use super::graph::NodeGraph;
+use super::node::Node;
+use super::registry::NodeTypeRegistry;
+use super::types::DataType;
+
+/// 流程类型
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum FlowType {
+    Login,
+    Discovery,
+    Search,
+    /// 在列表页之间翻页，位于发现/搜索与详情之间
+    Pagination,
+    Detail,
+    Content,
+}
+
+impl FlowType {
+    /// 所有流程类型，按典型执行顺序排列
+    pub fn all() -> &'static [FlowType] {
+        &[
+            Self::Login,
+            Self::Discovery,
+            Self::Search,
+            Self::Pagination,
+            Self::Detail,
+            Self::Content,
+        ]
+    }
+
+    /// 界面显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Login => "登录",
+            Self::Discovery => "发现",
+            Self::Search => "搜索",
+            Self::Pagination => "分页",
+            Self::Detail => "详情",
+            Self::Content => "内容",
+        }
+    }
+
+    /// 序列化使用的字符串标识
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Login => "login",
+            Self::Discovery => "discovery",
+            Self::Search => "search",
+            Self::Pagination => "pagination",
+            Self::Detail => "detail",
+            Self::Content => "content",
+        }
+    }
+
+    /// 可作为该类型流程入口的节点类型所带的标签，如 `search-entry`
+    pub fn entry_tag(&self) -> String {
+        format!("{}-entry", self.as_str())
+    }
+
+    /// 规则是否必须定义该流程
+    pub fn is_required(&self) -> bool {
+        matches!(self, Self::Search | Self::Detail | Self::Content)
+    }
+}
+
+impl fmt::Display for FlowType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+impl FromStr for FlowType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "login" => Ok(Self::Login),
+            "discovery" | "discover" | "explore" => Ok(Self::Discovery),
+            "search" => Ok(Self::Search),
+            "pagination" | "page" => Ok(Self::Pagination),
+            "detail" | "details" => Ok(Self::Detail),
+            "content" => Ok(Self::Content),
+            _ => Err(format!("未知的流程类型: {s}")),
+        }
+    }
+}
+
+/// 流程级别配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct FlowConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 流程运行时期望的参数，如搜索关键词、页码
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<FlowParameter>,
+    /// 覆盖规则全局 HTTP 配置的流程级配置，见 [`resolve_http`](crate::domain::resolve_http)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpConfig>,
+    /// 覆盖规则全局并发配置的流程级配置，通过 [`ConcurrencyConfig::merge_over`] 合并
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<ConcurrencyConfig>,
+    /// 流程对外输出的字段，见 [`Flow::validate_results`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub results: Vec<FlowResult>,
+}
+
+impl FlowConfig {
+    pub fn with_parameters(mut self, parameters: Vec<FlowParameter>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    pub fn with_results(mut self, results: Vec<FlowResult>) -> Self {
+        self.results = results;
+        self
+    }
+}
+
+/// 流程声明的输出字段
+///
+/// 出口节点没有输出端口，流程结果取自出口节点的输入端口，`name` 对应其端口 ID。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct FlowResult {
+    pub name: String,
+    pub data_type: DataType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl FlowResult {
+    pub fn new(name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            description: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+/// 单个流程
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Flow {
+    pub flow_type: FlowType,
+    #[serde(default)]
+    pub config: FlowConfig,
+    pub graph: NodeGraph,
+}
+
+impl Flow {
+    pub fn new(flow_type: FlowType, graph: NodeGraph) -> Self {
+        Self {
+            flow_type,
+            config: FlowConfig::default(),
+            graph,
+        }
+    }
+
+    pub fn with_config(mut self, config: FlowConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 入口节点：没有输入端口的节点，按 ID 排序，执行时从这些节点开始
+    pub fn entry_nodes(&self) -> Vec<&Node> {
+        self.nodes_where(|n| n.inputs.is_empty())
+    }
+
+    /// 出口节点：没有输出端口的节点，按 ID 排序，流程结果从这些节点收集
+    pub fn exit_nodes(&self) -> Vec<&Node> {
+        self.nodes_where(|n| n.outputs.is_empty())
+    }
+
+    fn nodes_where(&self, filter: impl Fn(&Node) -> bool) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self.graph.nodes.values().filter(|n| filter(n)).collect();
+        nodes.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+
+    /// 校验流程：节点图本身合法，且至少存在一个出口节点（没有输出端口的节点）
+    pub fn validate(&self) -> Result<()> {
+        self.graph.validate()?;
+        if self.exit_nodes().is_empty() {
+            return Err(DomainError::InvalidRule(format!(
+                "{}流程缺少出口节点",
+                self.flow_type.display_name()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 流程声明的输出字段
+    pub fn declared_results(&self) -> &[FlowResult] {
+        &self.config.results
+    }
+
+    /// 检查每个声明的结果都能从出口节点取得
+    ///
+    /// 结果名须是某个出口节点的输入端口 ID；出口节点没有输出端口，其输入即流程的输出。
+    pub fn validate_results(&self) -> std::result::Result<(), String> {
+        let exits = self.exit_nodes();
+        let missing: Vec<&str> = self
+            .declared_results()
+            .iter()
+            .filter(|r| !exits.iter().any(|n| n.get_input(&r.name).is_some()))
+            .map(|r| r.name.as_str())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "{}流程声明的结果 {} 没有对应的出口节点端口",
+            self.flow_type.display_name(),
+            missing.join(", ")
+        ))
+    }
+
+    /// 按 [`FlowConfig::parameters`] 检查运行时提供的参数，规则与
+    /// [`FlowTemplate::validate_params`] 相同
+    pub fn validate_params(
+        &self,
+        provided: &Map<String, Value>,
+    ) -> std::result::Result<(), String> {
+        template::validate_params(&self.config.parameters, provided)
+    }
+
+    /// 校验流程的入口节点符合流程类型的约定
+    ///
+    /// 入口节点指没有输入端口且未禁用的节点；至少一个入口节点的类型须在注册表中
+    /// 带有 [`FlowType::entry_tag`] 标签（忽略大小写）。未注册的类型视为不带标签。
+    pub fn validate_entry_for_type(
+        &self,
+        registry: &NodeTypeRegistry,
+    ) -> std::result::Result<(), String> {
+        let tag = self.flow_type.entry_tag();
+        let entries: Vec<&str> = self
+            .entry_nodes()
+            .into_iter()
+            .filter(|n| !n.disabled)
+            .map(|n| n.id.as_str())
+            .collect();
+        if entries.is_empty() {
+            return Err(format!("{}流程没有入口节点", self.flow_type.display_name()));
+        }
+
+        let tagged = entries.iter().any(|id| {
+            registry
+                .get(&self.graph.nodes[*id].node_type)
+                .is_some_and(|meta| meta.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)))
+        });
+        if tagged {
+            return Ok(());
+        }
+        Err(format!(
+            "{}流程的入口节点 {} 的类型都不带 {tag} 标签",
+            self.flow_type.display_name(),
+            entries.join(", ")
+        ))
+    }
+
+    /// 运行参数的 JSON Schema，供界面渲染“运行流程”表单
+    ///
+    /// 等同于以 [`FlowConfig::parameters`] 为声明参数的 [`Flow::parameter_schema_with`]。
+    pub fn parameter_schema(&self) -> Value {
+        self.parameter_schema_with(&self.config.parameters)
+    }
+
+    /// 结合声明的参数（通常来自流程所基于的模板）与图中出现的 `{{name}}`
+    /// 占位符生成运行参数的 JSON Schema
+    ///
+    /// 声明参数使用其类型、描述与默认值，必需且无默认值时列入 `required`；
+    /// 未声明的占位符视为必需的字符串参数。
+    pub fn parameter_schema_with(&self, declared: &[FlowParameter]) -> Value {
+        let mut properties = Map::new();
+        let mut required = BTreeSet::new();
+
+        for param in declared {
+            let mut prop = Map::new();
+            prop.insert("type".into(), json!(param.param_type.json_type()));
+            if let Some(description) = &param.description {
+                prop.insert("description".into(), json!(description));
+            }
+            if let Some(default) = &param.default {
+                prop.insert("default".into(), default.clone());
+            } else if param.required {
+                required.insert(param.name.clone());
+            }
+            properties.insert(param.name.clone(), Value::Object(prop));
+        }
+
+        let mut placeholders = BTreeSet::new();
+        for node in self.graph.nodes.values() {
+            template::collect_placeholders(&node.config, &mut placeholders);
+        }
+        for name in placeholders {
+            if !properties.contains_key(&name) {
+                properties.insert(name.clone(), json!({ "type": "string" }));
+                required.insert(name);
+            }
+        }
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required.into_iter().collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::graph::test_support::chain_graph;
+
+    #[test]
+    fn from_str_accepts_aliases() {
+        assert_eq!("Discover".parse::<FlowType>(), Ok(FlowType::Discovery));
+        assert_eq!("details".parse::<FlowType>(), Ok(FlowType::Detail));
+        assert!("unknown".parse::<FlowType>().is_err());
+    }
+
+    #[test]
+    fn pagination_flow_type_is_optional() {
+        assert_eq!("Page".parse::<FlowType>(), Ok(FlowType::Pagination));
+        assert_eq!("pagination".parse::<FlowType>(), Ok(FlowType::Pagination));
+        assert_eq!(FlowType::Pagination.display_name(), "分页");
+        assert_eq!(FlowType::Pagination.as_str(), "pagination");
+        assert!(!FlowType::Pagination.is_required());
+        assert!(FlowType::all().contains(&FlowType::Pagination));
+    }
+
+    #[test]
+    fn validate_entry_for_type_requires_tagged_entry() {
+        use crate::domain::{Node, NodeCategory, NodeTypeMetadata};
+
+        let registry = NodeTypeRegistry::builder()
+            .register(
+                NodeTypeMetadata::new("search_request", "搜索请求", NodeCategory::DataSource)
+                    .with_tags(["search-entry"]),
+            )
+            .register(NodeTypeMetadata::new(
+                "http_request",
+                "HTTP 请求",
+                NodeCategory::DataSource,
+            ))
+            .build();
+        let flow_from = |node_type: &str| {
+            let mut graph = crate::domain::NodeGraph::new();
+            graph.add_node(Node::new("entry", node_type)).unwrap();
+            Flow::new(FlowType::Search, graph)
+        };
+
+        assert!(flow_from("search_request")
+            .validate_entry_for_type(&registry)
+            .is_ok());
+        let err = flow_from("http_request")
+            .validate_entry_for_type(&registry)
+            .unwrap_err();
+        assert!(err.contains("search-entry"));
+    }
+
+    #[test]
+    fn required_flow_types() {
+        let required: Vec<_> = FlowType::all().iter().filter(|t| t.is_required()).collect();
+        assert_eq!(
+            required,
+            vec![&FlowType::Search, &FlowType::Detail, &FlowType::Content]
+        );
+    }
+
+    #[test]
+    fn parameter_schema_types_placeholders_as_strings() {
+        let mut graph = crate::domain::NodeGraph::new();
+        graph
+            .add_node(
+                crate::domain::Node::new("req", "http_request")
+                    .with_config(json!({ "url": "https://a.com/s?q={{query}}&p={{page}}" })),
+            )
+            .unwrap();
+        let flow = Flow::new(FlowType::Search, graph);
+
+        let schema = flow.parameter_schema();
+        assert_eq!(schema["type"], json!("object"));
+        assert_eq!(schema["properties"]["query"], json!({ "type": "string" }));
+        assert_eq!(schema["required"], json!(["page", "query"]));
+
+        let declared = [FlowParameter::new("page", ParamType::Number).with_default(json!(1))];
+        let schema = flow.parameter_schema_with(&declared);
+        assert_eq!(
+            schema["properties"]["page"],
+            json!({ "type": "number", "default": 1 })
+        );
+        assert_eq!(schema["required"], json!(["query"]));
+    }
+
+    #[test]
+    fn validate_params_uses_declared_flow_parameters() {
+        let config = FlowConfig::default().with_parameters(vec![
+            FlowParameter::new("keyword", ParamType::String).required(),
+            FlowParameter::new("page", ParamType::Number).with_default(json!(1)),
+        ]);
+        let flow = Flow::new(FlowType::Search, chain_graph(&["a"], &[])).with_config(config);
+
+        let mut provided = Map::new();
+        assert!(flow
+            .validate_params(&provided)
+            .unwrap_err()
+            .contains("keyword"));
+        provided.insert("keyword".into(), json!("cat"));
+        assert_eq!(flow.validate_params(&provided), Ok(()));
+        assert_eq!(flow.parameter_schema()["required"], json!(["keyword"]));
+
+        let legacy: FlowConfig = serde_json::from_value(json!({ "description": "旧" })).unwrap();
+        assert!(legacy.parameters.is_empty());
+    }
+
+    #[test]
+    fn entry_and_exit_nodes_follow_ports() {
+        let flow = Flow::new(
+            FlowType::Search,
+            chain_graph(&["c", "a", "b", "d"], &[("a", "b"), ("c", "b"), ("b", "d")]),
+        );
+        let ids = |nodes: Vec<&Node>| nodes.into_iter().map(|n| n.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(flow.entry_nodes()), vec!["a", "c"]);
+        assert_eq!(ids(flow.exit_nodes()), vec!["d"]);
+
+        let empty = Flow::new(FlowType::Search, NodeGraph::new());
+        assert!(empty.entry_nodes().is_empty());
+        assert!(empty.exit_nodes().is_empty());
+    }
+
+    #[test]
+    fn validate_results_matches_exit_node_inputs() {
+        let graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        let flow = Flow::new(FlowType::Search, graph.clone()).with_config(
+            FlowConfig::default().with_results(vec![FlowResult::new("in", DataType::Array)]),
+        );
+        assert_eq!(flow.declared_results().len(), 1);
+        assert_eq!(flow.validate_results(), Ok(()));
+
+        // `out` 只是中间节点 a 的输出端口，不是出口
+        let flow = Flow::new(FlowType::Search, graph).with_config(
+            FlowConfig::default().with_results(vec![
+                FlowResult::new("in", DataType::Array),
+                FlowResult::new("out", DataType::Any),
+                FlowResult::new("title", DataType::String),
+            ]),
+        );
+        let err = flow.validate_results().unwrap_err();
+        assert!(err.contains("out, title"), "{err}");
+
+        let legacy: FlowConfig = serde_json::from_value(json!({})).unwrap();
+        assert!(legacy.results.is_empty());
+    }
+
+    #[test]
+    fn validate_requires_exit_node() {
+        let flow = Flow::new(FlowType::Search, chain_graph(&["a", "b"], &[("a", "b")]));
+        assert!(flow.validate().is_ok());
+
+        let flow = Flow::new(FlowType::Search, crate::domain::NodeGraph::new());
+        assert!(matches!(flow.validate(), Err(DomainError::InvalidRule(_))));
+    }
+}
@@ -0,0 +1,349 @@
+//! 流程模板
+//!
+//! 模板是带参数的流程骨架：节点配置中的字符串可以包含 `{{name}}` 占位符，
+//! 实例化时由调用方提供的参数（或参数默认值）替换。
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use specta::Type;
+
+use super::{Flow, FlowConfig, FlowType};
+use crate::domain::error::{DomainError, Result};
+use crate::domain::graph::NodeGraph;
+use crate::domain::media::MediaType;
+
+/// 模板参数类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamType {
+    String,
+    Number,
+    Boolean,
+    Array,
+    Object,
+}
+
+impl ParamType {
+    /// 对应的 JSON Schema `type`
+    pub fn json_type(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::Array => "array",
+            Self::Object => "object",
+        }
+    }
+
+    /// `value` 的 JSON 类型是否与参数类型一致
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+        }
+    }
+}
+
+/// 模板声明的运行参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct FlowParameter {
+    pub name: String,
+    pub param_type: ParamType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+}
+
+impl FlowParameter {
+    pub fn new(name: impl Into<String>, param_type: ParamType) -> Self {
+        Self {
+            name: name.into(),
+            param_type,
+            description: None,
+            required: false,
+            default: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_default(mut self, default: Value) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// 检查参数声明本身：默认值的 JSON 类型必须与 `param_type` 一致
+    ///
+    /// 必需参数也可以带默认值，此时调用方未提供时使用默认值，不视为缺少参数。
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        match &self.default {
+            Some(default) if !self.param_type.matches(default) => Err(format!(
+                "参数 {} 的默认值 {default} 不是 {} 类型",
+                self.name,
+                self.param_type.json_type()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 流程模板
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct FlowTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub media_type: MediaType,
+    pub flow_type: FlowType,
+    #[serde(default)]
+    pub parameters: Vec<FlowParameter>,
+    pub graph: NodeGraph,
+}
+
+impl FlowTemplate {
+    /// 检查参数声明与提供的参数：每个声明都须通过 [`FlowParameter::validate`]，
+    /// 必需参数在没有默认值时必须提供
+    pub fn validate_params(
+        &self,
+        provided: &Map<String, Value>,
+    ) -> std::result::Result<(), String> {
+        validate_params(&self.parameters, provided)
+    }
+
+    /// 用参数实例化为流程
+    ///
+    /// 未提供的参数使用默认值；整个字符串恰好是一个占位符时替换为参数的原始
+    /// JSON 值，否则按字符串拼接。未声明且未提供的占位符保持原样。
+    pub fn instantiate(&self, provided: &Map<String, Value>) -> Result<Flow> {
+        self.validate_params(provided)
+            .map_err(DomainError::InvalidRule)?;
+
+        let mut params = Map::new();
+        for param in &self.parameters {
+            if let Some(default) = &param.default {
+                params.insert(param.name.clone(), default.clone());
+            }
+        }
+        for (key, value) in provided {
+            params.insert(key.clone(), value.clone());
+        }
+
+        let mut graph = self.graph.clone();
+        for node in graph.nodes.values_mut() {
+            substitute(&mut node.config, &params);
+        }
+
+        Ok(Flow::new(self.flow_type, graph).with_config(FlowConfig {
+            description: Some(self.name.clone()),
+            ..FlowConfig::default()
+        }))
+    }
+}
+
+/// 按参数声明检查提供的参数，见 [`FlowTemplate::validate_params`]
+pub(super) fn validate_params(
+    parameters: &[FlowParameter],
+    provided: &Map<String, Value>,
+) -> std::result::Result<(), String> {
+    for param in parameters {
+        param.validate()?;
+        if param.required && param.default.is_none() && !provided.contains_key(&param.name) {
+            return Err(format!("缺少必需参数: {}", param.name));
+        }
+    }
+    Ok(())
+}
+
+/// 提取字符串中的 `{{name}}` 占位符名称（去除两端空白）
+pub(crate) fn placeholders(s: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim();
+        if !name.is_empty() {
+            names.push(name);
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// 递归收集 JSON 值中所有字符串里出现的占位符名称
+pub(crate) fn collect_placeholders(value: &Value, out: &mut BTreeSet<String>) {
+    match value {
+        Value::String(s) => out.extend(placeholders(s).into_iter().map(str::to_string)),
+        Value::Array(items) => items.iter().for_each(|v| collect_placeholders(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_placeholders(v, out)),
+        _ => {}
+    }
+}
+
+fn substitute(value: &mut Value, params: &Map<String, Value>) {
+    match value {
+        Value::String(s) => {
+            if let Some(replaced) = substitute_str(s, params) {
+                *value = replaced;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| substitute(v, params)),
+        Value::Object(map) => map.values_mut().for_each(|v| substitute(v, params)),
+        _ => {}
+    }
+}
+
+fn substitute_str(s: &str, params: &Map<String, Value>) -> Option<Value> {
+    let names = placeholders(s);
+    if names.is_empty() {
+        return None;
+    }
+
+    let trimmed = s.trim();
+    if names.len() == 1 && trimmed.starts_with("{{") && trimmed.ends_with("}}") {
+        if let Some(value) = params.get(names[0]) {
+            return Some(value.clone());
+        }
+    }
+
+    let mut out = s.to_string();
+    for name in names {
+        if let Some(value) = params.get(name) {
+            let text = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            out = replace_placeholder(&out, name, &text);
+        }
+    }
+    Some(Value::String(out))
+}
+
+fn replace_placeholder(s: &str, name: &str, text: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) if after[..end].trim() == name => {
+                out.push_str(&rest[..start]);
+                out.push_str(text);
+                rest = &after[end + 2..];
+            }
+            _ => {
+                out.push_str(&rest[..start + 2]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Node, NodeGraph};
+    use serde_json::json;
+
+    fn template() -> FlowTemplate {
+        let mut graph = NodeGraph::new();
+        graph
+            .add_node(Node::new("req", "http_request").with_config(json!({
+                "url": "{{base_url}}/search?q={{ query }}",
+                "page": "{{page}}",
+            })))
+            .unwrap();
+        FlowTemplate {
+            id: "t".into(),
+            name: "搜索".into(),
+            description: String::new(),
+            media_type: MediaType::Video,
+            flow_type: FlowType::Search,
+            parameters: vec![
+                FlowParameter::new("base_url", ParamType::String).required(),
+                FlowParameter::new("query", ParamType::String).with_default(json!("")),
+                FlowParameter::new("page", ParamType::Number).with_default(json!(1)),
+            ],
+            graph,
+        }
+    }
+
+    #[test]
+    fn placeholders_are_extracted() {
+        assert_eq!(placeholders("{{a}}/x/{{ b }}/{{}}"), vec!["a", "b"]);
+        assert!(placeholders("no tokens {{").is_empty());
+    }
+
+    #[test]
+    fn instantiate_substitutes_params_and_defaults() {
+        let mut provided = Map::new();
+        provided.insert("base_url".into(), json!("https://a.com"));
+        provided.insert("query".into(), json!("cat"));
+        let flow = template().instantiate(&provided).unwrap();
+        let config = &flow.graph.nodes["req"].config;
+        assert_eq!(config["url"], json!("https://a.com/search?q=cat"));
+        assert_eq!(config["page"], json!(1));
+        assert_eq!(flow.flow_type, FlowType::Search);
+    }
+
+    #[test]
+    fn parameter_default_must_match_type() {
+        let cases = [
+            (ParamType::String, json!("x"), json!(1)),
+            (ParamType::Number, json!(1.5), json!("1")),
+            (ParamType::Boolean, json!(true), json!("true")),
+            (ParamType::Array, json!([1]), json!({})),
+            (ParamType::Object, json!({ "a": 1 }), json!([])),
+        ];
+        for (param_type, ok, bad) in cases {
+            let param = FlowParameter::new("p", param_type);
+            assert!(param.validate().is_ok());
+            assert!(param.clone().with_default(ok).validate().is_ok());
+            let err = param.with_default(bad).validate().unwrap_err();
+            assert!(err.contains(param_type.json_type()), "{err}");
+        }
+
+        let required_with_default = FlowParameter::new("page", ParamType::Number)
+            .required()
+            .with_default(json!(1));
+        assert!(required_with_default.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_params_rejects_mistyped_default() {
+        let mut template = template();
+        template.parameters[2].default = Some(json!("first"));
+        let mut provided = Map::new();
+        provided.insert("base_url".into(), json!("https://a.com"));
+        let err = template.validate_params(&provided).unwrap_err();
+        assert!(err.contains("page"));
+    }
+
+    #[test]
+    fn instantiate_requires_missing_params() {
+        assert!(matches!(
+            template().instantiate(&Map::new()),
+            Err(DomainError::InvalidRule(_))
+        ));
+    }
+}
@@ -0,0 +1,680 @@
+//! 图结构分析：环检测、拓扑排序与可达性
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use super::NodeGraph;
+use crate::domain::error::{Result, ValidationError};
+
+impl NodeGraph {
+    /// 检测图中的环，返回一个环上的节点序列（首尾为同一节点）
+    ///
+    /// 等价于取 [`NodeGraph::all_cycles`] 的第一项。
+    pub fn detect_cycle(&self) -> Option<Vec<String>> {
+        self.all_cycles().into_iter().next()
+    }
+
+    /// 找出图中所有相互独立的环
+    ///
+    /// 使用 Tarjan 算法求强连通分量，每个包含多个节点（或带自环）的分量报告一次：
+    /// 从分量中 ID 最小的节点出发，沿分量内的连接找一条回到自身的最短路径，
+    /// 首尾为同一节点。结果按起点 ID 排序。
+    pub fn all_cycles(&self) -> Vec<Vec<String>> {
+        struct Tarjan<'a> {
+            graph: &'a NodeGraph,
+            next_index: usize,
+            index: HashMap<&'a str, usize>,
+            low_link: HashMap<&'a str, usize>,
+            stack: Vec<&'a str>,
+            on_stack: HashSet<&'a str>,
+            components: Vec<Vec<&'a str>>,
+        }
+
+        impl<'a> Tarjan<'a> {
+            fn visit(&mut self, id: &'a str) {
+                self.index.insert(id, self.next_index);
+                self.low_link.insert(id, self.next_index);
+                self.next_index += 1;
+                self.stack.push(id);
+                self.on_stack.insert(id);
+
+                let graph = self.graph;
+                for c in graph.connections.iter().filter(|c| c.from_node == id) {
+                    let next = c.to_node.as_str();
+                    if !graph.nodes.contains_key(next) {
+                        continue;
+                    }
+                    if !self.index.contains_key(next) {
+                        self.visit(next);
+                        let low = self.low_link[id].min(self.low_link[next]);
+                        self.low_link.insert(id, low);
+                    } else if self.on_stack.contains(next) {
+                        let low = self.low_link[id].min(self.index[next]);
+                        self.low_link.insert(id, low);
+                    }
+                }
+
+                if self.low_link[id] == self.index[id] {
+                    let mut component = Vec::new();
+                    while let Some(member) = self.stack.pop() {
+                        self.on_stack.remove(member);
+                        component.push(member);
+                        if member == id {
+                            break;
+                        }
+                    }
+                    self.components.push(component);
+                }
+            }
+        }
+
+        let mut tarjan = Tarjan {
+            graph: self,
+            next_index: 0,
+            index: HashMap::new(),
+            low_link: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        };
+        let mut ids: Vec<&str> = self.nodes.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        for id in ids {
+            if !tarjan.index.contains_key(id) {
+                tarjan.visit(id);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = tarjan
+            .components
+            .into_iter()
+            .filter_map(|component| {
+                let members: HashSet<&str> = component.iter().copied().collect();
+                let start = component.iter().min()?;
+                self.cycle_within(start, &members)
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+
+    /// 在 `members` 内从 `start` 出发回到自身的最短路径（首尾为 `start`）
+    fn cycle_within(&self, start: &str, members: &HashSet<&str>) -> Option<Vec<String>> {
+        let mut parent: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(id) = queue.pop_front() {
+            for c in self.connections.iter().filter(|c| c.from_node == id) {
+                let next = c.to_node.as_str();
+                if next == start {
+                    let mut path = vec![start.to_string()];
+                    let mut current = id;
+                    while current != start {
+                        path.push(current.to_string());
+                        current = parent[current];
+                    }
+                    path.push(start.to_string());
+                    path.reverse();
+                    return Some(path);
+                }
+                if members.contains(next) && !parent.contains_key(next) {
+                    parent.insert(next, id);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// 拓扑排序：上游节点总是排在下游节点之前
+    ///
+    /// 使用递归 DFS 实现，存在环时返回 [`ValidationError::CycleDetected`]。
+    /// 没有依赖关系的节点之间的相对顺序取决于 `HashMap` 的迭代顺序，每次运行可能
+    /// 不同；需要可复现的顺序时使用 [`NodeGraph::topological_sort_stable`]。
+    pub fn topological_sort(&self) -> Result<Vec<String>> {
+        if let Some(path) = self.detect_cycle() {
+            return Err(ValidationError::CycleDetected { path }.into());
+        }
+
+        fn visit(
+            graph: &NodeGraph,
+            id: &str,
+            visited: &mut HashSet<String>,
+            order: &mut Vec<String>,
+        ) {
+            if !visited.insert(id.to_string()) {
+                return;
+            }
+            for dep in graph.get_dependencies(id) {
+                visit(graph, &dep, visited, order);
+            }
+            order.push(id.to_string());
+        }
+
+        let mut visited = HashSet::new();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        for id in self.nodes.keys() {
+            visit(self, id, &mut visited, &mut order);
+        }
+        Ok(order)
+    }
+
+    /// 结果确定的拓扑排序，用于可复现的执行日志与快照测试
+    ///
+    /// 使用 Kahn 算法，就绪节点存放在按 ID 排序的集合中，每次取出 ID 最小者，
+    /// 因此同一张图总是得到相同的顺序。指向不存在节点的连接被忽略。存在环时返回
+    /// [`ValidationError::CycleDetected`]。
+    pub fn topological_sort_stable(&self) -> Result<Vec<String>> {
+        let mut in_degree: HashMap<&str, usize> =
+            self.nodes.keys().map(|id| (id.as_str(), 0)).collect();
+        let mut dependents: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for c in &self.connections {
+            if !self.nodes.contains_key(&c.from_node) || !self.nodes.contains_key(&c.to_node) {
+                continue;
+            }
+            if dependents
+                .entry(c.from_node.as_str())
+                .or_default()
+                .insert(c.to_node.as_str())
+            {
+                *in_degree.entry(c.to_node.as_str()).or_default() += 1;
+            }
+        }
+
+        let mut ready: BTreeSet<&str> = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(id) = ready.pop_first() {
+            order.push(id.to_string());
+            for dependent in dependents.get(id).into_iter().flatten() {
+                let degree = in_degree.get_mut(dependent).expect("依赖节点已登记入度");
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.insert(dependent);
+                }
+            }
+        }
+
+        if order.len() < self.nodes.len() {
+            let path = self.detect_cycle().unwrap_or_default();
+            return Err(ValidationError::CycleDetected { path }.into());
+        }
+        Ok(order)
+    }
+
+    /// 最长依赖链（按节点数计）上的节点 ID，从入口到末端排列
+    ///
+    /// 即关键路径，用于估算执行耗时与在编辑器中显示图的“深度”。长度相同的链
+    /// 优先选择 ID 较小的节点，结果稳定。空图返回空列表，存在环时返回
+    /// [`ValidationError::CycleDetected`]。
+    pub fn longest_path(&self) -> Result<Vec<String>> {
+        let order = self.topological_sort()?;
+        // 节点 → (以它结尾的最长链长度, 链上的前一个节点)
+        let mut best: HashMap<&str, (usize, Option<String>)> = HashMap::new();
+        for id in &order {
+            let mut deps = self.get_dependencies(id);
+            deps.sort();
+            let mut entry = (1, None);
+            for dep in deps {
+                let Some((len, _)) = best.get(dep.as_str()) else {
+                    continue;
+                };
+                if len + 1 > entry.0 {
+                    entry = (len + 1, Some(dep));
+                }
+            }
+            best.insert(id.as_str(), entry);
+        }
+
+        let Some(mut current) = best
+            .iter()
+            .max_by(|(a_id, (a_len, _)), (b_id, (b_len, _))| {
+                a_len.cmp(b_len).then_with(|| b_id.cmp(a_id))
+            })
+            .map(|(id, _)| id.to_string())
+        else {
+            return Ok(Vec::new());
+        };
+        let mut path = vec![current.clone()];
+        while let Some((_, Some(prev))) = best.get(current.as_str()) {
+            path.push(prev.clone());
+            current = prev.clone();
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    /// 只包含未禁用节点的拓扑排序
+    ///
+    /// 禁用的节点被移除，与之相连的连接视为不存在。注意这会切断数据流：链条中间
+    /// 的节点被禁用后，下游节点的必需输入端口实际上不再有数据来源，本方法不会
+    /// 报告这类问题，调用方需要另行校验。只经过禁用节点的环不会导致错误。
+    pub fn topological_sort_active(&self) -> Result<Vec<String>> {
+        self.active_subgraph().topological_sort()
+    }
+
+    /// 去掉禁用节点及其连接后的子图
+    fn active_subgraph(&self) -> NodeGraph {
+        let active: HashSet<String> = self
+            .nodes
+            .values()
+            .filter(|n| !n.disabled)
+            .map(|n| n.id.clone())
+            .collect();
+        self.subgraph(&active)
+    }
+
+    /// 按依赖层级分组的节点 ID：第 N 层的节点只依赖前 N-1 层中的节点，
+    /// 同一层的节点可以并发执行；每层内按 ID 排序
+    ///
+    /// 使用 Kahn 算法逐层剥离入度为零的节点。禁用的节点同样参与分层，需要跳过时
+    /// 使用 [`NodeGraph::execution_layers_active`]。存在环时返回
+    /// [`ValidationError::CycleDetected`]。
+    pub fn execution_layers(&self) -> Result<Vec<Vec<String>>> {
+        let mut in_degree: HashMap<&str, usize> =
+            self.nodes.keys().map(|id| (id.as_str(), 0)).collect();
+        let mut dependents: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for c in &self.connections {
+            if !self.nodes.contains_key(&c.from_node) || !self.nodes.contains_key(&c.to_node) {
+                continue;
+            }
+            if dependents
+                .entry(c.from_node.as_str())
+                .or_default()
+                .insert(c.to_node.as_str())
+            {
+                *in_degree.entry(c.to_node.as_str()).or_default() += 1;
+            }
+        }
+
+        let mut current: Vec<&str> = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut layers = Vec::new();
+        let mut placed = 0;
+        while !current.is_empty() {
+            current.sort_unstable();
+            let mut next = Vec::new();
+            for id in &current {
+                for dependent in dependents.get(id).into_iter().flatten() {
+                    let degree = in_degree.get_mut(dependent).expect("依赖节点已登记入度");
+                    *degree -= 1;
+                    if *degree == 0 {
+                        next.push(*dependent);
+                    }
+                }
+            }
+            placed += current.len();
+            layers.push(current.iter().map(|id| id.to_string()).collect());
+            current = next;
+        }
+
+        if placed < self.nodes.len() {
+            let path = self.detect_cycle().unwrap_or_else(|| {
+                let mut remaining: Vec<String> = in_degree
+                    .into_iter()
+                    .filter(|(_, degree)| *degree > 0)
+                    .map(|(id, _)| id.to_string())
+                    .collect();
+                remaining.sort();
+                remaining
+            });
+            return Err(ValidationError::CycleDetected { path }.into());
+        }
+        Ok(layers)
+    }
+
+    /// 只包含未禁用节点的执行层级
+    ///
+    /// 与 [`NodeGraph::topological_sort_active`] 一样先移除禁用节点及其连接再分层，
+    /// 因此只依赖禁用节点的节点会提前到更靠前的层，只经过禁用节点的环不会导致错误。
+    pub fn execution_layers_active(&self) -> Result<Vec<Vec<String>>> {
+        self.active_subgraph().execution_layers()
+    }
+
+    /// 能够到达指定出口节点的入口节点（没有输入端口的节点），按 ID 排序
+    ///
+    /// 从出口节点沿连接反向遍历，在入口节点处停止。用于多入口流程中
+    /// 提示某个输出由哪些数据源提供。节点不存在或不可从任何入口到达时返回空列表。
+    pub fn source_entries(&self, exit_node_id: &str) -> Vec<String> {
+        if !self.nodes.contains_key(exit_node_id) {
+            return Vec::new();
+        }
+
+        let mut entries = Vec::new();
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut queue = VecDeque::from([exit_node_id.to_string()]);
+        visited.insert(exit_node_id.to_string());
+
+        while let Some(id) = queue.pop_front() {
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            if node.inputs.is_empty() {
+                entries.push(id);
+                continue;
+            }
+            for dep in self.get_dependencies(&id) {
+                if visited.insert(dep.clone()) {
+                    queue.push_back(dep);
+                }
+            }
+        }
+
+        entries.sort();
+        entries
+    }
+
+    /// 无法从任何入口节点到达的出口节点（没有输出端口的节点）ID，按 ID 排序
+    ///
+    /// 返回的是**不可达**的出口：它们不被任何入口节点供给数据。对每个出口节点做一次
+    /// 反向广度优先遍历（见 [`NodeGraph::source_entries`]），找不到入口节点的出口
+    /// 永远不会产生数据，对应 [`ValidationError::ExitNotFed`]。
+    /// 没有任何端口的节点自身就是入口，不会出现在结果中。
+    pub fn exit_nodes_reachable(&self) -> Vec<String> {
+        let mut unfed: Vec<String> = self
+            .nodes
+            .values()
+            .filter(|n| n.outputs.is_empty())
+            .filter(|n| self.source_entries(&n.id).is_empty())
+            .map(|n| n.id.clone())
+            .collect();
+        unfed.sort();
+        unfed
+    }
+
+    /// 图是一条简单链时，按执行顺序返回节点 ID，否则返回 `None`
+    ///
+    /// 简单链要求每个节点至多一条入边、一条出边，且所有节点首尾相连成一条路径；
+    /// 分支、汇合、环或多个互不相连的片段都会返回 `None`。空图视为空链。
+    /// 编辑器的“列表视图”只能展示这类流程。
+    pub fn as_linear_pipeline(&self) -> Option<Vec<String>> {
+        if self.nodes.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let mut next: HashMap<&str, &str> = HashMap::new();
+        let mut has_incoming: HashSet<&str> = HashSet::new();
+        for c in &self.connections {
+            if next
+                .insert(c.from_node.as_str(), c.to_node.as_str())
+                .is_some()
+            {
+                return None;
+            }
+            if !has_incoming.insert(c.to_node.as_str()) {
+                return None;
+            }
+        }
+
+        let mut starts = self
+            .nodes
+            .keys()
+            .filter(|id| !has_incoming.contains(id.as_str()));
+        let start = starts.next()?;
+        if starts.next().is_some() {
+            return None;
+        }
+
+        let mut order = vec![start.clone()];
+        let mut current = start.as_str();
+        while let Some(to) = next.get(current) {
+            if order.len() >= self.nodes.len() {
+                return None;
+            }
+            order.push(to.to_string());
+            current = to;
+        }
+
+        (order.len() == self.nodes.len()).then_some(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::graph::test_support::*;
+    use crate::domain::{Connection, DomainError, ValidationError};
+
+    fn diamond() -> crate::domain::NodeGraph {
+        chain_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")],
+        )
+    }
+
+    #[test]
+    fn detect_cycle_finds_loop() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        assert!(graph.detect_cycle().is_none());
+
+        graph.get_node_mut("c").unwrap().outputs = node("c", &[], &["out"]).outputs;
+        graph
+            .add_connection(Connection::new("c", "out", "b", "in"))
+            .unwrap();
+        let cycle = graph.detect_cycle().unwrap();
+        assert_eq!(cycle.first(), cycle.last());
+        assert!(cycle.contains(&"b".to_string()) && cycle.contains(&"c".to_string()));
+    }
+
+    #[test]
+    fn execution_layers_group_independent_nodes() {
+        let layers = diamond().execution_layers().unwrap();
+        assert_eq!(layers, vec![vec!["a"], vec!["b", "c"], vec!["d"]]);
+    }
+
+    #[test]
+    fn execution_layers_active_skips_disabled_nodes() {
+        let mut graph = diamond();
+        graph.get_node_mut("b").unwrap().disabled = true;
+        assert_eq!(graph.execution_layers().unwrap()[1], vec!["b", "c"]);
+        assert_eq!(
+            graph.execution_layers_active().unwrap(),
+            vec![vec!["a"], vec!["c"], vec!["d"]]
+        );
+    }
+
+    #[test]
+    fn execution_layers_active_ignores_disabled_dependencies() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.get_node_mut("b").unwrap().disabled = true;
+        assert_eq!(
+            graph.execution_layers_active().unwrap(),
+            vec![vec!["a", "c"]]
+        );
+
+        let mut graph = chain_graph(&["a", "b"], &[("a", "b"), ("b", "a")]);
+        assert!(graph.execution_layers_active().is_err());
+        graph.get_node_mut("b").unwrap().disabled = true;
+        assert_eq!(graph.execution_layers_active().unwrap(), vec![vec!["a"]]);
+    }
+
+    #[test]
+    fn execution_layers_report_cycles() {
+        let mut graph = diamond();
+        graph.get_node_mut("d").unwrap().outputs = node("d", &[], &["out"]).outputs;
+        graph
+            .add_connection(Connection::new("d", "out", "b", "in"))
+            .unwrap();
+        assert!(matches!(
+            graph.execution_layers(),
+            Err(DomainError::Validation(
+                ValidationError::CycleDetected { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn all_cycles_reports_each_independent_cycle() {
+        let mut graph = chain_graph(
+            &["a", "b", "c", "x", "y", "z"],
+            &[
+                ("a", "b"),
+                ("b", "a"),
+                ("b", "c"),
+                ("x", "y"),
+                ("y", "z"),
+                ("z", "x"),
+            ],
+        );
+        assert_eq!(
+            graph.all_cycles(),
+            vec![vec!["a", "b", "a"], vec!["x", "y", "z", "x"]]
+        );
+
+        graph.get_node_mut("c").unwrap().outputs = node("c", &[], &["out"]).outputs;
+        graph
+            .add_connection(Connection::new("c", "out", "c", "in"))
+            .unwrap();
+        assert_eq!(graph.all_cycles().len(), 3);
+        assert!(graph
+            .all_cycles()
+            .contains(&vec!["c".to_string(), "c".to_string()]));
+        assert_eq!(
+            graph.detect_cycle(),
+            Some(vec!["a".into(), "b".into(), "a".into()])
+        );
+    }
+
+    #[test]
+    fn topological_sort_active_excludes_disabled_middle_node() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.get_node_mut("b").unwrap().disabled = true;
+        let mut order = graph.topological_sort_active().unwrap();
+        order.sort();
+        assert_eq!(order, vec!["a", "c"]);
+        assert_eq!(graph.topological_sort().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn longest_path_follows_the_long_branch() {
+        let graph = chain_graph(
+            &["start", "short", "l1", "l2", "l3", "end"],
+            &[
+                ("start", "short"),
+                ("short", "end"),
+                ("start", "l1"),
+                ("l1", "l2"),
+                ("l2", "l3"),
+                ("l3", "end"),
+            ],
+        );
+        assert_eq!(
+            graph.longest_path().unwrap(),
+            vec!["start", "l1", "l2", "l3", "end"]
+        );
+        assert!(crate::domain::NodeGraph::new()
+            .longest_path()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn topological_sort_orders_dependencies_first() {
+        let graph = diamond();
+        let order = graph.topological_sort().unwrap();
+        let pos = |id: &str| order.iter().position(|n| n == id).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(pos("a") < pos("b") && pos("a") < pos("c"));
+        assert!(pos("b") < pos("d") && pos("c") < pos("d"));
+    }
+
+    #[test]
+    fn topological_sort_rejects_cycles() {
+        let mut graph = crate::domain::NodeGraph::new();
+        graph.add_node(node("a", &["in"], &["out"])).unwrap();
+        graph.add_node(node("b", &["in"], &["out"])).unwrap();
+        graph
+            .add_connection(Connection::new("a", "out", "b", "in"))
+            .unwrap();
+        graph
+            .add_connection(Connection::new("b", "out", "a", "in"))
+            .unwrap();
+        assert!(matches!(
+            graph.topological_sort(),
+            Err(DomainError::Validation(
+                ValidationError::CycleDetected { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn topological_sort_stable_breaks_ties_by_id() {
+        let edges = [
+            ("z", "b"),
+            ("m", "a"),
+            ("b", "end"),
+            ("a", "end"),
+            ("y", "end"),
+        ];
+        let graph = chain_graph(&["z", "m", "b", "a", "y", "end"], &edges);
+        let order = graph.topological_sort_stable().unwrap();
+        assert_eq!(order, vec!["m", "a", "y", "z", "b", "end"]);
+        let reordered = chain_graph(&["end", "y", "a", "b", "m", "z"], &edges);
+        assert_eq!(reordered.topological_sort_stable().unwrap(), order);
+
+        let cyclic = chain_graph(&["a", "b"], &[("a", "b"), ("b", "a")]);
+        assert!(matches!(
+            cyclic.topological_sort_stable(),
+            Err(DomainError::Validation(
+                ValidationError::CycleDetected { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn source_entries_lists_shared_entry_once() {
+        let graph = diamond();
+        assert_eq!(graph.source_entries("d"), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn source_entries_collects_every_contributing_entry() {
+        let graph = chain_graph(&["a", "b", "c", "x"], &[("a", "c"), ("b", "c")]);
+        assert_eq!(
+            graph.source_entries("c"),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert!(graph.source_entries("missing").is_empty());
+    }
+
+    #[test]
+    fn linear_pipeline_returns_chain_order() {
+        let graph = chain_graph(&["c", "a", "b"], &[("a", "b"), ("b", "c")]);
+        assert_eq!(
+            graph.as_linear_pipeline(),
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+    }
+
+    #[test]
+    fn linear_pipeline_rejects_branching_graphs() {
+        assert_eq!(diamond().as_linear_pipeline(), None);
+
+        let disjoint = chain_graph(&["a", "b", "c"], &[("a", "b")]);
+        assert_eq!(disjoint.as_linear_pipeline(), None);
+    }
+
+    #[test]
+    fn source_entries_empty_when_not_fed_by_entry() {
+        let mut graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        graph.add_node(node("orphan", &["in"], &[])).unwrap();
+        assert!(graph.source_entries("orphan").is_empty());
+    }
+
+    #[test]
+    fn exit_nodes_reachable_reports_exits_without_sources() {
+        let mut graph = chain_graph(
+            &["a", "b", "p", "q", "r", "solo"],
+            &[("a", "b"), ("p", "q"), ("q", "p"), ("q", "r")],
+        );
+        graph.add_node(node("orphan", &["in"], &[])).unwrap();
+        assert_eq!(
+            graph.exit_nodes_reachable(),
+            vec!["orphan".to_string(), "r".to_string()]
+        );
+        assert!(diamond().exit_nodes_reachable().is_empty());
+    }
+}
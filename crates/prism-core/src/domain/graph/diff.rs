@@ -0,0 +1,144 @@
+//! 节点图结构差异
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::NodeGraph;
+use crate::domain::connection::Connection;
+
+/// 两个版本节点图之间的结构差异
+///
+/// 节点以 ID 对应；同一 ID 的节点类型或配置不同即视为修改。所有列表均已排序，
+/// 便于直接展示或比较。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<String>,
+    pub removed_nodes: Vec<String>,
+    pub modified_nodes: Vec<String>,
+    pub added_connections: Vec<Connection>,
+    pub removed_connections: Vec<Connection>,
+}
+
+impl GraphDiff {
+    /// 两个图在结构上是否没有任何差异
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.modified_nodes.is_empty()
+            && self.added_connections.is_empty()
+            && self.removed_connections.is_empty()
+    }
+}
+
+/// 计算从 `old` 到 `new` 的结构差异
+pub fn diff(old: &NodeGraph, new: &NodeGraph) -> GraphDiff {
+    let mut added_nodes: Vec<String> = new
+        .nodes
+        .keys()
+        .filter(|id| !old.nodes.contains_key(*id))
+        .cloned()
+        .collect();
+    let mut removed_nodes: Vec<String> = old
+        .nodes
+        .keys()
+        .filter(|id| !new.nodes.contains_key(*id))
+        .cloned()
+        .collect();
+    let mut modified_nodes: Vec<String> = old
+        .nodes
+        .values()
+        .filter_map(|before| {
+            let after = new.nodes.get(&before.id)?;
+            (after.node_type != before.node_type || after.config != before.config)
+                .then(|| before.id.clone())
+        })
+        .collect();
+    added_nodes.sort();
+    removed_nodes.sort();
+    modified_nodes.sort();
+
+    GraphDiff {
+        added_nodes,
+        removed_nodes,
+        modified_nodes,
+        added_connections: connections_missing_from(&new.connections, &old.connections),
+        removed_connections: connections_missing_from(&old.connections, &new.connections),
+    }
+}
+
+/// `connections` 中不在 `other` 里的连接，按 [`Connection::key`] 排序并去重
+fn connections_missing_from(connections: &[Connection], other: &[Connection]) -> Vec<Connection> {
+    let other: HashSet<&Connection> = other.iter().collect();
+    let mut missing: Vec<Connection> = connections
+        .iter()
+        .filter(|c| !other.contains(c))
+        .cloned()
+        .collect();
+    missing.sort_by_key(Connection::key);
+    missing.dedup();
+    missing
+}
+
+impl NodeGraph {
+    /// 计算从本图到 `other` 的结构差异，见 [`diff`]
+    pub fn diff(&self, other: &NodeGraph) -> GraphDiff {
+        diff(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::graph::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_reports_added_node_and_connection() {
+        let old = chain_graph(&["a", "b"], &[("a", "b")]);
+        let new = chain_graph(&["a", "b", "c"], &[("a", "b"), ("a", "c")]);
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added_nodes, vec!["c"]);
+        assert!(diff.removed_nodes.is_empty());
+        assert!(diff.modified_nodes.is_empty());
+        assert_eq!(
+            diff.added_connections,
+            vec![Connection::new("a", "out", "c", "in")]
+        );
+        assert!(diff.removed_connections.is_empty());
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_removed_nodes_and_connections() {
+        let old = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let new = chain_graph(&["a", "b"], &[("a", "b")]);
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.removed_nodes, vec!["c"]);
+        assert_eq!(
+            diff.removed_connections,
+            vec![Connection::new("b", "out", "c", "in")]
+        );
+        assert!(diff.added_nodes.is_empty() && diff.added_connections.is_empty());
+        // b 失去了输出端口，但类型与配置未变，不算修改
+        assert!(diff.modified_nodes.is_empty());
+    }
+
+    #[test]
+    fn diff_reports_type_and_config_changes_as_modified() {
+        let old = chain_graph(&["a", "b", "c"], &[]);
+        let mut new = old.clone();
+        new.get_node_mut("a").unwrap().node_type = "css_selector".into();
+        new.get_node_mut("b")
+            .unwrap()
+            .set_config("selector", json!(".title"));
+        new.get_node_mut("c").unwrap().position = Some(crate::domain::Position { x: 1.0, y: 2.0 });
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.modified_nodes, vec!["a", "b"]);
+        assert!(diff.added_nodes.is_empty() && diff.removed_nodes.is_empty());
+    }
+}
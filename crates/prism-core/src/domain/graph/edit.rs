@@ -0,0 +1,642 @@
+//! 节点图的结构编辑
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde_json::Value;
+
+use super::NodeGraph;
+use crate::domain::connection::Connection;
+use crate::domain::error::{Result, ValidationError};
+use crate::domain::node::Node;
+
+impl NodeGraph {
+    /// 克隆整张图，并对每个节点 ID 应用 `f`，连接两端同步改写
+    ///
+    /// 这是合并、拼接、深拷贝等需要重新分配 ID 的操作的基础。本方法不检查冲突：
+    /// 若 `f` 把多个 ID 映射为同一个值，结果中只会保留其中一个节点，
+    /// 调用方可以通过比较前后 `nodes.len()` 来发现冲突。
+    pub fn map_node_ids(&self, f: impl Fn(&str) -> String) -> NodeGraph {
+        let nodes = self
+            .nodes
+            .values()
+            .map(|node| {
+                let mut node = node.clone();
+                node.id = f(&node.id);
+                (node.id.clone(), node)
+            })
+            .collect();
+        let connections = self
+            .connections
+            .iter()
+            .map(|c| {
+                let mut c = c.clone();
+                c.from_node = f(&c.from_node);
+                c.to_node = f(&c.to_node);
+                c
+            })
+            .collect();
+        NodeGraph {
+            nodes,
+            connections,
+            config: self.config.clone(),
+        }
+    }
+
+    /// 深拷贝整张图并为每个节点分配新的 UUID，返回新图与旧 ID → 新 ID 的映射
+    ///
+    /// 用于复制流程后再粘贴回原图而不发生 ID 冲突；节点的位置、配置等其余字段原样保留。
+    pub fn clone_with_new_ids(&self) -> (NodeGraph, HashMap<String, String>) {
+        let mapping: HashMap<String, String> = self
+            .nodes
+            .keys()
+            .map(|id| (id.clone(), uuid::Uuid::new_v4().to_string()))
+            .collect();
+        let graph =
+            self.map_node_ids(|id| mapping.get(id).cloned().unwrap_or_else(|| id.to_string()));
+        (graph, mapping)
+    }
+
+    /// 把 `other` 的节点与连接并入本图，`other` 中的每个节点 ID 都加上 `id_prefix`
+    ///
+    /// `other` 内部的连接两端同步改写。加前缀后的 ID 与本图已有节点冲突时返回
+    /// [`ValidationError::DuplicateNodeId`]，此时本图不做任何修改。合并结果保留本图的配置。
+    pub fn merge(&mut self, other: NodeGraph, id_prefix: &str) -> Result<()> {
+        let other = other.map_node_ids(|id| format!("{id_prefix}{id}"));
+        let mut collisions: Vec<&String> = other
+            .nodes
+            .keys()
+            .filter(|id| self.nodes.contains_key(*id))
+            .collect();
+        collisions.sort();
+        if let Some(node_id) = collisions.first() {
+            return Err(ValidationError::DuplicateNodeId {
+                node_id: node_id.to_string(),
+            }
+            .into());
+        }
+
+        self.nodes.extend(other.nodes);
+        for connection in other.connections {
+            if !self.connections.contains(&connection) {
+                self.connections.push(connection);
+            }
+        }
+        Ok(())
+    }
+
+    /// 修改节点 ID，节点自身的 `id` 与所有相关连接的两端同步改写
+    ///
+    /// `old_id` 不存在时返回 [`ValidationError::NodeNotFound`]，`new_id` 已被其他
+    /// 节点占用时返回 [`ValidationError::DuplicateNodeId`]。新旧 ID 相同时不做修改。
+    pub fn rename_node(&mut self, old_id: &str, new_id: &str) -> Result<()> {
+        if !self.nodes.contains_key(old_id) {
+            return Err(ValidationError::NodeNotFound {
+                node_id: old_id.to_string(),
+            }
+            .into());
+        }
+        if old_id == new_id {
+            return Ok(());
+        }
+        if self.nodes.contains_key(new_id) {
+            return Err(ValidationError::DuplicateNodeId {
+                node_id: new_id.to_string(),
+            }
+            .into());
+        }
+
+        let mut node = self.nodes.remove(old_id).expect("节点存在已检查");
+        node.id = new_id.to_string();
+        self.nodes.insert(node.id.clone(), node);
+        for c in &mut self.connections {
+            if c.from_node == old_id {
+                c.from_node = new_id.to_string();
+            }
+            if c.to_node == old_id {
+                c.to_node = new_id.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// 修改节点的端口 ID，并同步改写连在该端口上的连接
+    ///
+    /// `is_input` 为真时改写输入端口与连接的 `to_port`，否则改写输出端口与连接的
+    /// `from_port`。节点不存在时返回 [`ValidationError::NodeNotFound`]，其余错误见
+    /// [`Node::rename_input_port`](crate::domain::Node::rename_input_port)。
+    pub fn rename_port(
+        &mut self,
+        node_id: &str,
+        old_port: &str,
+        new_port: &str,
+        is_input: bool,
+    ) -> Result<()> {
+        let node = self
+            .nodes
+            .get_mut(node_id)
+            .ok_or_else(|| ValidationError::NodeNotFound {
+                node_id: node_id.to_string(),
+            })?;
+        if is_input {
+            node.rename_input_port(old_port, new_port)?;
+        } else {
+            node.rename_output_port(old_port, new_port)?;
+        }
+        for c in &mut self.connections {
+            if is_input && c.to_node == node_id && c.to_port == old_port {
+                c.to_port = new_port.to_string();
+            }
+            if !is_input && c.from_node == node_id && c.from_port == old_port {
+                c.from_port = new_port.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// 把节点从图中摘除，并将其上游直接连到下游，返回新建的连接
+    ///
+    /// 输入端口与输出端口一一配对：各只有一个时直接配对；多于一个时按数据类型配对，
+    /// 每个输入端口须恰好对应一个同类型的输出端口。配对后，连到某个输入端口的每个
+    /// 上游端点都连向对应输出端口的每个下游端点。
+    ///
+    /// 节点不存在时返回 [`ValidationError::NodeNotFound`]；输入与输出端口数量不一致、
+    /// 或无法按类型唯一配对时返回 [`ValidationError::InvalidNodeConfig`]；新连接超出
+    /// 下游端口的连接数限制时返回 [`ValidationError::PortOverConnected`]。出错时本图
+    /// 不做任何修改。已存在的连接不会重复创建，也不出现在返回值中。
+    pub fn bypass_node(&mut self, node_id: &str) -> Result<Vec<Connection>> {
+        let node = self.require_node(node_id)?;
+        let pairs = bypass_pairs(node).map_err(|reason| ValidationError::InvalidNodeConfig {
+            node_id: node.id.clone(),
+            node_type: node.node_type.clone(),
+            reason,
+        })?;
+
+        let (inbound, outbound) = self.connections_for_node(node_id);
+        let mut created = Vec::new();
+        for (input, output) in &pairs {
+            for upstream in inbound
+                .iter()
+                .filter(|c| &c.to_port == input && c.from_node != node_id)
+            {
+                for downstream in outbound
+                    .iter()
+                    .filter(|c| &c.from_port == output && c.to_node != node_id)
+                {
+                    let connection = Connection::new(
+                        &upstream.from_node,
+                        &upstream.from_port,
+                        &downstream.to_node,
+                        &downstream.to_port,
+                    );
+                    if !self.connections.contains(&connection) && !created.contains(&connection) {
+                        created.push(connection);
+                    }
+                }
+            }
+        }
+
+        let mut graph = self.clone();
+        graph.remove_node(node_id)?;
+        for connection in &created {
+            graph.add_connection(connection.clone())?;
+        }
+        *self = graph;
+        Ok(created)
+    }
+
+    /// 把所有节点配置中字符串值里的 `find` 替换为 `replace`，返回替换的次数
+    ///
+    /// 递归遍历对象与数组，只改写字符串值，不改动对象的键。用于站点域名等变化时
+    /// 批量迁移规则；`find` 为空时不做任何替换。
+    pub fn replace_in_config(&mut self, find: &str, replace: &str) -> usize {
+        if find.is_empty() {
+            return 0;
+        }
+        self.nodes
+            .values_mut()
+            .map(|node| replace_in_value(&mut node.config, find, replace))
+            .sum()
+    }
+
+    /// 由选中节点导出的子图，用于复制选区
+    ///
+    /// 复制选中的节点（含配置）以及两端都在选区内的连接，伸出选区的连接直接丢弃；
+    /// 不存在的 ID 被忽略。子图沿用本图的 [`GraphConfig`](super::GraphConfig)。
+    pub fn subgraph(&self, node_ids: &HashSet<String>) -> NodeGraph {
+        NodeGraph {
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|(id, _)| node_ids.contains(*id))
+                .map(|(id, node)| (id.clone(), node.clone()))
+                .collect(),
+            connections: self
+                .connections
+                .iter()
+                .filter(|c| node_ids.contains(&c.from_node) && node_ids.contains(&c.to_node))
+                .cloned()
+                .collect(),
+            config: self.config.clone(),
+        }
+    }
+
+    /// 合并等价的重复节点，返回被合并掉的节点数
+    ///
+    /// 两个节点等价当且仅当类型、配置、端口声明与禁用状态都相同，并且入边集合
+    /// （上游节点、上游端口、输入端口）完全一致且非空——即它们以相同的输入做相同的事。
+    /// 每组等价节点保留 ID 最小者，其余节点的出边改接到保留节点后删除。合并可能使
+    /// 下游节点变得等价，因此会重复执行直到不再有可合并的节点。没有入边的节点不会被合并。
+    pub fn dedup_equivalent_nodes(&mut self) -> usize {
+        let mut merged = 0;
+        loop {
+            let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for node in self.nodes.values() {
+                let mut incoming: Vec<(&str, &str, &str)> = self
+                    .connections
+                    .iter()
+                    .filter(|c| c.to_node == node.id)
+                    .map(|c| {
+                        (
+                            c.from_node.as_str(),
+                            c.from_port.as_str(),
+                            c.to_port.as_str(),
+                        )
+                    })
+                    .collect();
+                if incoming.is_empty() {
+                    continue;
+                }
+                incoming.sort_unstable();
+                let signature = (
+                    &node.node_type,
+                    &node.config,
+                    &node.inputs,
+                    &node.outputs,
+                    node.disabled,
+                    incoming,
+                );
+                let Ok(key) = serde_json::to_string(&signature) else {
+                    continue;
+                };
+                groups.entry(key).or_default().push(node.id.clone());
+            }
+
+            let mut changed = false;
+            for mut ids in groups.into_values().filter(|ids| ids.len() > 1) {
+                ids.sort();
+                let (keep, duplicates) = ids.split_first().expect("分组至少有两个节点");
+                for duplicate in duplicates {
+                    self.merge_node_into(duplicate, keep);
+                    merged += 1;
+                    changed = true;
+                }
+            }
+            if !changed {
+                return merged;
+            }
+        }
+    }
+
+    /// 删除 `duplicate` 及其入边，并把它的出边改接到 `keep`
+    fn merge_node_into(&mut self, duplicate: &str, keep: &str) {
+        self.nodes.remove(duplicate);
+        let mut connections = Vec::with_capacity(self.connections.len());
+        for mut c in std::mem::take(&mut self.connections) {
+            if c.to_node == duplicate {
+                continue;
+            }
+            if c.from_node == duplicate {
+                c.from_node = keep.to_string();
+            }
+            if !connections.contains(&c) {
+                connections.push(c);
+            }
+        }
+        self.connections = connections;
+    }
+}
+
+fn replace_in_value(value: &mut Value, find: &str, replace: &str) -> usize {
+    match value {
+        Value::String(s) => {
+            let count = s.matches(find).count();
+            if count > 0 {
+                *s = s.replace(find, replace);
+            }
+            count
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|v| replace_in_value(v, find, replace))
+            .sum(),
+        Value::Object(map) => map
+            .values_mut()
+            .map(|v| replace_in_value(v, find, replace))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// 旁路节点时输入端口与输出端口的配对，见 [`NodeGraph::bypass_node`]
+fn bypass_pairs(node: &Node) -> std::result::Result<Vec<(String, String)>, String> {
+    if node.inputs.len() != node.outputs.len() {
+        return Err(format!(
+            "有 {} 个输入端口、{} 个输出端口，无法旁路",
+            node.inputs.len(),
+            node.outputs.len()
+        ));
+    }
+    if let ([input], [output]) = (node.inputs.as_slice(), node.outputs.as_slice()) {
+        return Ok(vec![(input.id.clone(), output.id.clone())]);
+    }
+    let mut pairs = Vec::with_capacity(node.inputs.len());
+    for input in &node.inputs {
+        let mut same_type = node
+            .outputs
+            .iter()
+            .filter(|output| output.data_type == input.data_type);
+        match (same_type.next(), same_type.next()) {
+            (Some(output), None) => pairs.push((input.id.clone(), output.id.clone())),
+            _ => {
+                return Err(format!(
+                    "输入端口 {} 没有唯一的同类型输出端口，无法旁路",
+                    input.id
+                ))
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::graph::test_support::*;
+    use crate::domain::{Connection, DomainError, NodeGraph, ValidationError};
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    #[test]
+    fn map_node_ids_preserves_connectivity() {
+        let graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let mapped = graph.map_node_ids(|id| id.to_uppercase());
+
+        assert_eq!(mapped.nodes.len(), 3);
+        assert_eq!(mapped.nodes["B"].id, "B");
+        assert_eq!(mapped.get_dependencies("B"), vec!["A".to_string()]);
+        assert_eq!(mapped.get_dependents("B"), vec!["C".to_string()]);
+        assert!(mapped.validate().is_ok());
+        assert_eq!(
+            mapped.topological_sort().unwrap().len(),
+            graph.topological_sort().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn map_node_ids_collisions_shrink_node_count() {
+        let graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        let mapped = graph.map_node_ids(|_| "same".to_string());
+        assert!(mapped.nodes.len() < graph.nodes.len());
+    }
+
+    #[test]
+    fn clone_with_new_ids_preserves_structure() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let b = graph.get_node_mut("b").unwrap();
+        b.set_config("selector", json!(".item"));
+        b.position = Some(crate::domain::Position { x: 10.0, y: 20.0 });
+
+        let (copy, mapping) = graph.clone_with_new_ids();
+        assert_eq!(mapping.len(), 3);
+        assert!(copy.nodes.keys().all(|id| !graph.nodes.contains_key(id)));
+        let new_b = &copy.nodes[&mapping["b"]];
+        assert_eq!(new_b.id, mapping["b"]);
+        assert_eq!(new_b.config, graph.nodes["b"].config);
+        assert_eq!(new_b.position, graph.nodes["b"].position);
+        assert!(copy.connections.contains(&Connection::new(
+            &mapping["a"],
+            "out",
+            &mapping["b"],
+            "in"
+        )));
+        assert_eq!(copy.validate().is_ok(), graph.validate().is_ok());
+        assert_eq!(copy.validate_all().len(), graph.validate_all().len());
+    }
+
+    #[test]
+    fn merge_prefixes_ids_and_rewrites_internal_edges() {
+        let mut graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        graph.config.name = Some("主图".into());
+        let fragment = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c"), ("a", "c")]);
+
+        graph.merge(fragment, "frag.").unwrap();
+        assert_eq!(graph.nodes.len(), 5);
+        assert_eq!(graph.nodes["frag.b"].id, "frag.b");
+        assert_eq!(graph.config.name.as_deref(), Some("主图"));
+        let keys: Vec<String> = graph.connections_sorted().iter().map(|c| c.key()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "a.out->b.in",
+                "frag.a.out->frag.b.in",
+                "frag.a.out->frag.c.in",
+                "frag.b.out->frag.c.in",
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_rejects_colliding_ids_without_changes() {
+        let mut graph = chain_graph(&["x.a", "b"], &[]);
+        let before = graph.clone();
+        let err = graph
+            .merge(chain_graph(&["a", "c"], &[("a", "c")]), "x.")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DomainError::Validation(ValidationError::DuplicateNodeId { ref node_id }) if node_id == "x.a"
+        ));
+        assert_eq!(graph, before);
+    }
+
+    #[test]
+    fn rename_node_rewrites_both_edge_directions() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.rename_node("b", "middle").unwrap();
+
+        assert!(!graph.nodes.contains_key("b"));
+        assert_eq!(graph.nodes["middle"].id, "middle");
+        let keys: Vec<String> = graph.connections.iter().map(|c| c.key()).collect();
+        assert_eq!(keys, vec!["a.out->middle.in", "middle.out->c.in"]);
+        assert!(graph.validate().is_ok());
+    }
+
+    #[test]
+    fn rename_node_rejects_missing_and_taken_ids() {
+        let mut graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        assert!(matches!(
+            graph.rename_node("zz", "y"),
+            Err(DomainError::Validation(
+                ValidationError::NodeNotFound { .. }
+            ))
+        ));
+        assert!(matches!(
+            graph.rename_node("a", "b"),
+            Err(DomainError::Validation(
+                ValidationError::DuplicateNodeId { .. }
+            ))
+        ));
+        graph.rename_node("a", "a").unwrap();
+        assert!(graph.nodes.contains_key("a"));
+    }
+
+    #[test]
+    fn rename_port_rewrites_connected_edges() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.rename_port("b", "in", "items", true).unwrap();
+        graph.rename_port("b", "out", "result", false).unwrap();
+
+        assert!(graph.nodes["b"].get_input("items").is_some());
+        let keys: Vec<String> = graph.connections.iter().map(|c| c.key()).collect();
+        assert_eq!(keys, vec!["a.out->b.items", "b.result->c.in"]);
+        assert!(graph.validate().is_ok());
+
+        assert!(matches!(
+            graph.rename_port("b", "in", "x", true),
+            Err(DomainError::Validation(
+                ValidationError::PortNotFound { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn bypass_node_reconnects_upstream_to_downstream() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let created = graph.bypass_node("b").unwrap();
+
+        assert_eq!(created, vec![Connection::new("a", "out", "c", "in")]);
+        assert!(!graph.nodes.contains_key("b"));
+        assert_eq!(graph.connections, created);
+        assert!(graph.validate().is_ok());
+    }
+
+    #[test]
+    fn bypass_node_rejects_mismatched_ports_without_changes() {
+        let mut graph = NodeGraph::new();
+        graph.add_node(node("split", &["in"], &["a", "b"])).unwrap();
+        let before = graph.clone();
+        assert!(matches!(
+            graph.bypass_node("split"),
+            Err(DomainError::Validation(
+                ValidationError::InvalidNodeConfig { .. }
+            ))
+        ));
+        assert!(matches!(
+            graph.bypass_node("missing"),
+            Err(DomainError::Validation(
+                ValidationError::NodeNotFound { .. }
+            ))
+        ));
+        assert_eq!(graph, before);
+    }
+
+    #[test]
+    fn replace_in_config_rewrites_string_values_only() {
+        let mut graph = chain_graph(&["list", "detail", "title"], &[]);
+        graph.get_node_mut("list").unwrap().set_config(
+            "url",
+            json!("https://old.example.com/list?next=https://old.example.com/2"),
+        );
+        graph.get_node_mut("detail").unwrap().set_config(
+            "request",
+            json!({ "url": "https://old.example.com/item", "retries": 2 }),
+        );
+        graph
+            .get_node_mut("title")
+            .unwrap()
+            .set_config("old.example.com", json!(["h1"]));
+
+        assert_eq!(
+            graph.replace_in_config("old.example.com", "new.example.com"),
+            3
+        );
+        assert_eq!(
+            graph.nodes["list"].config["url"],
+            "https://new.example.com/list?next=https://new.example.com/2"
+        );
+        assert_eq!(
+            graph.nodes["detail"].config["request"],
+            json!({ "url": "https://new.example.com/item", "retries": 2 })
+        );
+        assert_eq!(
+            graph.nodes["title"].config,
+            json!({ "old.example.com": ["h1"] })
+        );
+        assert_eq!(graph.replace_in_config("", "x"), 0);
+    }
+
+    #[test]
+    fn subgraph_drops_edges_leaving_selection() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.config.name = Some("原图".into());
+        graph
+            .get_node_mut("b")
+            .unwrap()
+            .set_config("selector", json!(".item"));
+        let selection: HashSet<String> = ["a", "b", "missing"].map(String::from).into();
+
+        let sub = graph.subgraph(&selection);
+        let mut ids: Vec<&String> = sub.nodes.keys().collect();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(
+            sub.connections,
+            vec![Connection::new("a", "out", "b", "in")]
+        );
+        assert_eq!(sub.nodes["b"].config, json!({ "selector": ".item" }));
+        assert_eq!(sub.config, graph.config);
+    }
+
+    fn selector_fanout() -> NodeGraph {
+        let mut graph = NodeGraph::new();
+        graph.add_node(node("src", &[], &["out"])).unwrap();
+        for id in ["sel1", "sel2"] {
+            graph
+                .add_node(node(id, &["in"], &["out"]).with_config(json!({ "selector": ".title" })))
+                .unwrap();
+            graph
+                .add_connection(Connection::new("src", "out", id, "in"))
+                .unwrap();
+        }
+        for (sel, out) in [("sel1", "out1"), ("sel2", "out2")] {
+            graph
+                .add_node(node(out, &["in"], &[]).with_config(json!({ "name": out })))
+                .unwrap();
+            graph
+                .add_connection(Connection::new(sel, "out", out, "in"))
+                .unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn dedup_merges_identical_selectors() {
+        let mut graph = selector_fanout();
+        assert_eq!(graph.dedup_equivalent_nodes(), 1);
+        assert!(!graph.nodes.contains_key("sel2"));
+        assert_eq!(graph.get_dependents("sel1"), vec!["out1", "out2"]);
+        assert_eq!(graph.connections.len(), 3);
+        assert!(graph.validate().is_ok());
+        assert_eq!(graph.dedup_equivalent_nodes(), 0);
+    }
+
+    #[test]
+    fn dedup_keeps_nodes_with_different_config() {
+        let mut graph = selector_fanout();
+        graph
+            .get_node_mut("sel2")
+            .unwrap()
+            .set_config("selector", json!(".cover"));
+        assert_eq!(graph.dedup_equivalent_nodes(), 0);
+        assert_eq!(graph.nodes.len(), 5);
+    }
+}
@@ -0,0 +1,179 @@
+//! 节点图导出为可视化文本格式（Graphviz DOT、Mermaid）
+//!
+//! 输出按节点 ID 与连接顺序排序，同一张图总是得到相同的文本。
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use super::NodeGraph;
+
+impl NodeGraph {
+    /// 导出为 Graphviz DOT 格式的 `digraph`
+    ///
+    /// 每个节点标注为 `id\ntype`，每条连接标注为 `from_port->to_port`；禁用的节点
+    /// 使用虚线，处于环上的节点（见 [`NodeGraph::detect_cycle`]）标红。
+    pub fn to_dot(&self) -> String {
+        let in_cycle: HashSet<String> = self
+            .detect_cycle()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let name = self.config.name.as_deref().unwrap_or("graph");
+
+        let mut out = format!("digraph {} {{\n", dot_quote(name));
+        for node in self.nodes_sorted() {
+            let mut attrs = vec![format!(
+                "label={}",
+                dot_quote(&format!("{}\n{}", node.id, node.node_type))
+            )];
+            if node.disabled {
+                attrs.push("style=dashed".to_string());
+            }
+            if in_cycle.contains(&node.id) {
+                attrs.push("color=red".to_string());
+            }
+            let _ = writeln!(out, "    {} [{}];", dot_quote(&node.id), attrs.join(", "));
+        }
+        for c in self.connections_sorted() {
+            let _ = writeln!(
+                out,
+                "    {} -> {} [label={}];",
+                dot_quote(&c.from_node),
+                dot_quote(&c.to_node),
+                dot_quote(&format!("{}->{}", c.from_port, c.to_port))
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// 导出为 Mermaid `flowchart TD`，用于 Markdown 文档与帮助面板
+    ///
+    /// Mermaid 不接受部分字符作为节点 ID，因此每个节点使用净化后的别名，真实 ID
+    /// 与类型写在标签中；连接以 `-->|from_port → to_port|` 的形式标注端口。
+    pub fn to_mermaid(&self) -> String {
+        let aliases = self.mermaid_aliases();
+        let mut out = String::from("flowchart TD\n");
+        for node in self.nodes_sorted() {
+            let _ = writeln!(
+                out,
+                "    {}[\"{}<br/>{}\"]",
+                aliases[&node.id],
+                mermaid_escape(&node.id),
+                mermaid_escape(&node.node_type)
+            );
+        }
+        for c in self.connections_sorted() {
+            let (Some(from), Some(to)) = (aliases.get(&c.from_node), aliases.get(&c.to_node))
+            else {
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "    {from} -->|{} → {}| {to}",
+                mermaid_escape(&c.from_port),
+                mermaid_escape(&c.to_port)
+            );
+        }
+        out
+    }
+
+    /// 节点 ID → Mermaid 安全别名
+    ///
+    /// 非 ASCII 字母数字的字符替换为 `_`，并加 `n_` 前缀以避开 `end` 等关键字；
+    /// 净化后重名时追加序号。
+    fn mermaid_aliases(&self) -> HashMap<String, String> {
+        let mut used = HashSet::new();
+        let mut aliases = HashMap::new();
+        for node in self.nodes_sorted() {
+            let base: String = node
+                .id
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            let base = format!("n_{base}");
+            let mut alias = base.clone();
+            let mut suffix = 1;
+            while !used.insert(alias.clone()) {
+                suffix += 1;
+                alias = format!("{base}_{suffix}");
+            }
+            aliases.insert(node.id.clone(), alias);
+        }
+        aliases
+    }
+}
+
+/// DOT 双引号字符串，转义反斜杠、引号与换行
+fn dot_quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+/// 转义 Mermaid 标签中的特殊字符
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+        .replace('|', "#124;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::graph::test_support::*;
+    use crate::domain::Connection;
+
+    #[test]
+    fn to_dot_is_deterministic_and_styled() {
+        let mut graph = chain_graph(&["c", "a", "b"], &[("b", "c"), ("a", "b")]);
+        graph.get_node_mut("c").unwrap().disabled = true;
+
+        let dot = graph.to_dot();
+        assert_eq!(
+            dot,
+            "digraph \"graph\" {\n\
+             \x20   \"a\" [label=\"a\\ntest\"];\n\
+             \x20   \"b\" [label=\"b\\ntest\"];\n\
+             \x20   \"c\" [label=\"c\\ntest\", style=dashed];\n\
+             \x20   \"a\" -> \"b\" [label=\"out->in\"];\n\
+             \x20   \"b\" -> \"c\" [label=\"out->in\"];\n\
+             }\n"
+        );
+        assert_eq!(graph.to_dot(), dot);
+    }
+
+    #[test]
+    fn to_mermaid_emits_header_and_edges() {
+        let graph = chain_graph(
+            &["fetch-page", "parse.list"],
+            &[("fetch-page", "parse.list")],
+        );
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("    n_fetch_page[\"fetch-page<br/>test\"]\n"));
+        assert!(mermaid.contains("    n_fetch_page -->|out → in| n_parse_list\n"));
+    }
+
+    #[test]
+    fn mermaid_aliases_stay_unique_after_sanitizing() {
+        let graph = chain_graph(&["a-b", "a.b"], &[]);
+        let aliases = graph.mermaid_aliases();
+        assert_eq!(aliases["a-b"], "n_a_b");
+        assert_eq!(aliases["a.b"], "n_a_b_2");
+    }
+
+    #[test]
+    fn to_dot_marks_cycle_nodes_red() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.get_node_mut("c").unwrap().outputs = node("c", &[], &["out"]).outputs;
+        graph
+            .add_connection(Connection::new("c", "out", "b", "in"))
+            .unwrap();
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"b\" [label=\"b\\ntest\", color=red];"));
+        assert!(dot.contains("\"a\" [label=\"a\\ntest\"];"));
+    }
+}
@@ -0,0 +1,55 @@
+//! 节点图的自动布局
+
+use super::NodeGraph;
+use crate::domain::error::Result;
+use crate::domain::node::Position;
+
+impl NodeGraph {
+    /// 按执行层级整理节点坐标，用于编辑器的“整理”操作
+    ///
+    /// 第 N 层（见 [`NodeGraph::execution_layers`]）的节点 X 坐标为 `N * h_spacing`，
+    /// 层内按 ID 顺序第 M 个节点的 Y 坐标为 `M * v_spacing`，结果只取决于图结构。
+    /// `force` 为假时已有坐标的节点保持不动（但仍占据层内的位置）。存在环时返回
+    /// [`ValidationError::CycleDetected`](crate::domain::ValidationError::CycleDetected)，
+    /// 此时不修改任何节点。
+    pub fn auto_layout(&mut self, h_spacing: i64, v_spacing: i64, force: bool) -> Result<()> {
+        for (column, layer) in self.execution_layers()?.into_iter().enumerate() {
+            for (row, id) in layer.into_iter().enumerate() {
+                let node = self.nodes.get_mut(&id).expect("分层结果中的节点存在");
+                if force || node.position.is_none() {
+                    node.position = Some(Position {
+                        x: (column as i64 * h_spacing) as f64,
+                        y: (row as i64 * v_spacing) as f64,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::graph::test_support::*;
+    use crate::domain::Position;
+
+    #[test]
+    fn auto_layout_places_layers_left_to_right() {
+        let mut graph = chain_graph(&["a", "b", "c", "e"], &[("a", "c"), ("b", "c"), ("c", "e")]);
+        let fixed = Position { x: 5.0, y: 5.0 };
+        graph.get_node_mut("e").unwrap().position = Some(fixed);
+
+        graph.auto_layout(200, 100, false).unwrap();
+        let position = |id: &str| graph.nodes[id].position.unwrap();
+        assert_eq!(position("a"), Position { x: 0.0, y: 0.0 });
+        assert_eq!(position("b"), Position { x: 0.0, y: 100.0 });
+        assert_eq!(position("c"), Position { x: 200.0, y: 0.0 });
+        assert_eq!(position("e"), fixed);
+
+        graph.auto_layout(200, 100, true).unwrap();
+        assert_eq!(
+            graph.nodes["e"].position,
+            Some(Position { x: 400.0, y: 0.0 })
+        );
+    }
+}
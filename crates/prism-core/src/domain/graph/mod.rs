@@ -0,0 +1,596 @@
+//! 节点图
+//!
+//! [`NodeGraph`] 是一条流程的执行单元：节点以 ID 为键存放，连接以有向边的形式
+//! 从上游节点的输出端口指向下游节点的输入端口。
+
+mod analysis;
+mod diff;
+mod edit;
+mod export;
+mod layout;
+mod stats;
+mod validation;
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+pub use self::diff::{diff, GraphDiff};
+pub use self::stats::GraphStats;
+
+use super::connection::Connection;
+use super::error::{Result, ValidationError};
+use super::node::Node;
+use super::types::{TypeCoercion, TypeCompatibility};
+
+/// 图级别配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct GraphConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 校验连接时的类型转换策略，未设置时为 [`TypeCoercion::Strict`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_coercion: Option<TypeCoercion>,
+    /// 自定义的类型兼容规则，设置后优先于 `type_coercion`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_compatibility: Option<TypeCompatibility>,
+}
+
+impl GraphConfig {
+    /// 实际生效的类型转换策略
+    pub fn type_coercion(&self) -> TypeCoercion {
+        self.type_coercion.unwrap_or_default()
+    }
+
+    /// 实际生效的类型兼容规则：优先使用 `type_compatibility`，否则取
+    /// `type_coercion` 策略对应的规则
+    pub fn type_compatibility(&self) -> TypeCompatibility {
+        self.type_compatibility
+            .clone()
+            .unwrap_or_else(|| self.type_coercion().compatibility())
+    }
+}
+
+/// 由节点与连接组成的有向图
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct NodeGraph {
+    #[serde(default)]
+    pub nodes: HashMap<String, Node>,
+    #[serde(default)]
+    pub connections: Vec<Connection>,
+    #[serde(default)]
+    pub config: GraphConfig,
+}
+
+impl NodeGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: GraphConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// 添加节点
+    ///
+    /// 节点须通过 [`Node::validate`]；ID 已存在时返回 [`ValidationError::DuplicateNodeId`]。
+    pub fn add_node(&mut self, node: Node) -> Result<()> {
+        node.validate()?;
+        if self.nodes.contains_key(&node.id) {
+            return Err(ValidationError::DuplicateNodeId { node_id: node.id }.into());
+        }
+        self.nodes.insert(node.id.clone(), node);
+        Ok(())
+    }
+
+    /// 图中尚未使用的节点 ID：`{prefix}_{n}`，`n` 取使其不冲突的最小正整数
+    pub fn next_node_id(&self, prefix: &str) -> String {
+        (1..)
+            .map(|n| format!("{prefix}_{n}"))
+            .find(|id| !self.nodes.contains_key(id))
+            .expect("节点数有限，总能找到未使用的 ID")
+    }
+
+    pub fn get_node(&self, node_id: &str) -> Option<&Node> {
+        self.nodes.get(node_id)
+    }
+
+    pub fn get_node_mut(&mut self, node_id: &str) -> Option<&mut Node> {
+        self.nodes.get_mut(node_id)
+    }
+
+    /// 删除节点及其所有相关连接
+    pub fn remove_node(&mut self, node_id: &str) -> Result<Node> {
+        let node = self
+            .nodes
+            .remove(node_id)
+            .ok_or_else(|| ValidationError::NodeNotFound {
+                node_id: node_id.to_string(),
+            })?;
+        self.connections
+            .retain(|c| c.from_node != node_id && c.to_node != node_id);
+        Ok(node)
+    }
+
+    /// 添加连接
+    ///
+    /// 两端节点和端口必须存在；与已有连接完全相同的连接会被忽略。目标端口的连接数
+    /// 已达到 [`Port::connection_limit`](crate::domain::Port::connection_limit) 时返回
+    /// [`ValidationError::PortOverConnected`]。
+    /// 类型兼容性不在此处检查，由 [`NodeGraph::validate`] 负责。
+    pub fn add_connection(&mut self, connection: Connection) -> Result<()> {
+        let from = self.require_node(&connection.from_node)?;
+        if from.get_output(&connection.from_port).is_none() {
+            return Err(ValidationError::PortNotFound {
+                node_id: connection.from_node.clone(),
+                port_id: connection.from_port.clone(),
+            }
+            .into());
+        }
+        let to = self.require_node(&connection.to_node)?;
+        let Some(to_port) = to.get_input(&connection.to_port) else {
+            return Err(ValidationError::PortNotFound {
+                node_id: connection.to_node.clone(),
+                port_id: connection.to_port.clone(),
+            }
+            .into());
+        };
+        if self.connections.contains(&connection) {
+            return Ok(());
+        }
+        let count = self.input_connection_count(&connection.to_node, &connection.to_port);
+        if to_port
+            .connection_limit()
+            .is_some_and(|limit| count >= limit)
+        {
+            return Err(ValidationError::PortOverConnected {
+                node_id: connection.to_node,
+                port_id: connection.to_port,
+                count: count + 1,
+            }
+            .into());
+        }
+        self.connections.push(connection);
+        Ok(())
+    }
+
+    /// 删除一条连接，返回是否确有连接被删除；不存在时返回 `Ok(false)`，便于幂等调用
+    pub fn remove_connection(&mut self, connection: &Connection) -> Result<bool> {
+        let before = self.connections.len();
+        self.connections.retain(|c| c != connection);
+        Ok(self.connections.len() != before)
+    }
+
+    /// 删除连在指定端口（输入或输出）上的所有连接，返回删除的数量
+    pub fn remove_connections_for_port(&mut self, node_id: &str, port_id: &str) -> usize {
+        let before = self.connections.len();
+        self.connections.retain(|c| {
+            !((c.from_node == node_id && c.from_port == port_id)
+                || (c.to_node == node_id && c.to_port == port_id))
+        });
+        before - self.connections.len()
+    }
+
+    /// 连到指定输入端口的连接数
+    pub fn input_connection_count(&self, node_id: &str, port_id: &str) -> usize {
+        self.connections
+            .iter()
+            .filter(|c| c.to_node == node_id && c.to_port == port_id)
+            .count()
+    }
+
+    /// 以端点 `(节点 ID, 端口 ID)` 为键的连接索引，值为连接在 `connections` 中的下标
+    ///
+    /// 每条连接同时登记在来源端点和目标端点下，便于编辑器反复查询某个端口的
+    /// 连接数或占用情况而不必每次扫描全部连接。索引是构建时的快照，图被修改后
+    /// （增删节点或连接、重排连接）必须重新构建。
+    pub fn connections_by_endpoint(&self) -> HashMap<(String, String), Vec<usize>> {
+        let mut index: HashMap<(String, String), Vec<usize>> = HashMap::new();
+        for (i, c) in self.connections.iter().enumerate() {
+            index
+                .entry((c.from_node.clone(), c.from_port.clone()))
+                .or_default()
+                .push(i);
+            index
+                .entry((c.to_node.clone(), c.to_port.clone()))
+                .or_default()
+                .push(i);
+        }
+        index
+    }
+
+    /// 与节点相关的连接，返回 `(入边, 出边)`，各自保持 `connections` 中的顺序
+    ///
+    /// 自环同时出现在两侧。
+    pub fn connections_for_node(&self, node_id: &str) -> (Vec<&Connection>, Vec<&Connection>) {
+        let mut inbound = Vec::new();
+        let mut outbound = Vec::new();
+        for c in &self.connections {
+            if c.to_node == node_id {
+                inbound.push(c);
+            }
+            if c.from_node == node_id {
+                outbound.push(c);
+            }
+        }
+        (inbound, outbound)
+    }
+
+    /// 直接上游节点 ID（去重）
+    pub fn get_dependencies(&self, node_id: &str) -> Vec<String> {
+        let (inbound, _) = self.connections_for_node(node_id);
+        let mut deps: Vec<String> = Vec::new();
+        for c in inbound {
+            if !deps.contains(&c.from_node) {
+                deps.push(c.from_node.clone());
+            }
+        }
+        deps
+    }
+
+    /// 直接下游节点 ID（去重）
+    pub fn get_dependents(&self, node_id: &str) -> Vec<String> {
+        let (_, outbound) = self.connections_for_node(node_id);
+        let mut dependents: Vec<String> = Vec::new();
+        for c in outbound {
+            if !dependents.contains(&c.to_node) {
+                dependents.push(c.to_node.clone());
+            }
+        }
+        dependents
+    }
+
+    /// 按 `(from_node, from_port, to_node, to_port)` 对连接排序
+    ///
+    /// 连接以 `Vec` 存储，编辑过程中的顺序变化会在序列化结果中产生无意义的差异；
+    /// 序列化前调用本方法可以得到稳定的输出，规则指纹、导出与仓库写入都会先排序。
+    /// 排序不改变图的语义。
+    pub fn sort_connections(&mut self) {
+        self.connections.sort_by(connection_order);
+    }
+
+    /// 按 ID 排序的节点视图，不修改图
+    pub fn nodes_sorted(&self) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self.nodes.values().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+
+    /// 指定类型的所有节点，按 ID 排序
+    pub fn find_by_type(&self, node_type: &str) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self
+            .nodes
+            .values()
+            .filter(|n| n.node_type == node_type)
+            .collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+
+    /// 同 [`NodeGraph::find_by_type`]，返回可变引用，用于批量改写配置
+    pub fn find_by_type_mut(&mut self, node_type: &str) -> Vec<&mut Node> {
+        let mut nodes: Vec<&mut Node> = self
+            .nodes
+            .values_mut()
+            .filter(|n| n.node_type == node_type)
+            .collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+
+    /// 按连接键排序的连接视图，顺序与 [`NodeGraph::sort_connections`] 一致，不修改图
+    pub fn connections_sorted(&self) -> Vec<&Connection> {
+        let mut connections: Vec<&Connection> = self.connections.iter().collect();
+        connections.sort_by(|a, b| connection_order(a, b));
+        connections
+    }
+
+    fn require_node(&self, node_id: &str) -> Result<&Node> {
+        self.nodes.get(node_id).ok_or_else(|| {
+            ValidationError::NodeNotFound {
+                node_id: node_id.to_string(),
+            }
+            .into()
+        })
+    }
+}
+
+/// 连接的规范顺序：依次比较 `(from_node, from_port, to_node, to_port)`
+fn connection_order(a: &Connection, b: &Connection) -> Ordering {
+    (&a.from_node, &a.from_port, &a.to_node, &a.to_port).cmp(&(
+        &b.from_node,
+        &b.from_port,
+        &b.to_node,
+        &b.to_port,
+    ))
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use crate::domain::{Connection, DataType, Node, NodeGraph, Port};
+
+    /// 构造一个端口均为 `Any` 类型的节点
+    pub fn node(id: &str, inputs: &[&str], outputs: &[&str]) -> Node {
+        let mut node = Node::new(id, "test");
+        for port in inputs {
+            node = node.with_input(Port::new(*port, *port, DataType::Any));
+        }
+        for port in outputs {
+            node = node.with_output(Port::new(*port, *port, DataType::Any));
+        }
+        node
+    }
+
+    /// 以 `out -> in` 端口连接的方式构造图，每个节点都有 `in`/`out` 端口，
+    /// 没有入边的节点不带输入端口（即入口节点），没有出边的节点不带输出端口；
+    /// `in` 端口可接受多个连接，便于构造分支汇合
+    pub fn chain_graph(ids: &[&str], edges: &[(&str, &str)]) -> NodeGraph {
+        let mut graph = NodeGraph::new();
+        for id in ids {
+            let has_in = edges.iter().any(|(_, to)| to == id);
+            let has_out = edges.iter().any(|(from, _)| from == id);
+            let inputs: &[&str] = if has_in { &["in"] } else { &[] };
+            let outputs: &[&str] = if has_out { &["out"] } else { &[] };
+            let mut node = node(id, inputs, outputs);
+            for port in &mut node.inputs {
+                port.multiple = true;
+            }
+            graph.add_node(node).unwrap();
+        }
+        for (from, to) in edges {
+            graph
+                .add_connection(Connection::new(*from, "out", *to, "in"))
+                .unwrap();
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::*;
+    use super::*;
+    use crate::domain::{DataType, DomainError, Port};
+    use serde_json::json;
+
+    #[test]
+    fn connections_by_endpoint_indexes_both_sides() {
+        let graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("a", "c"), ("b", "c")]);
+        let index = graph.connections_by_endpoint();
+        let key = |node: &str, port: &str| (node.to_string(), port.to_string());
+
+        assert_eq!(index[&key("a", "out")].len(), 2);
+        assert_eq!(index[&key("c", "in")].len(), 2);
+        for i in &index[&key("b", "in")] {
+            assert_eq!(graph.connections[*i].to_node, "b");
+        }
+        assert!(!index.contains_key(&key("a", "in")));
+    }
+
+    #[test]
+    fn connections_for_node_splits_inbound_and_outbound() {
+        let graph = chain_graph(&["a", "b", "c", "d"], &[("a", "b"), ("b", "c"), ("b", "d")]);
+        let (inbound, outbound) = graph.connections_for_node("b");
+        assert_eq!(inbound, vec![&Connection::new("a", "out", "b", "in")]);
+        let targets: Vec<&str> = outbound.iter().map(|c| c.to_node.as_str()).collect();
+        assert_eq!(targets, vec!["c", "d"]);
+
+        let (inbound, outbound) = graph.connections_for_node("missing");
+        assert!(inbound.is_empty() && outbound.is_empty());
+    }
+
+    #[test]
+    fn remove_connection_is_idempotent() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let edge = Connection::new("a", "out", "b", "in");
+        assert!(graph.remove_connection(&edge).unwrap());
+        assert!(!graph.remove_connection(&edge).unwrap());
+        assert_eq!(
+            graph.connections,
+            vec![Connection::new("b", "out", "c", "in")]
+        );
+        assert_eq!(graph.nodes.len(), 3);
+    }
+
+    #[test]
+    fn remove_connections_for_port_detaches_all_edges() {
+        let mut graph = chain_graph(&["a", "b", "c", "d"], &[("a", "c"), ("b", "c"), ("c", "d")]);
+        assert_eq!(graph.remove_connections_for_port("c", "in"), 2);
+        assert_eq!(graph.remove_connections_for_port("c", "in"), 0);
+        assert_eq!(graph.remove_connections_for_port("c", "out"), 1);
+        assert!(graph.connections.is_empty());
+    }
+
+    #[test]
+    fn multiple_input_port_accepts_fan_in() {
+        let mut graph = NodeGraph::new();
+        graph.add_node(node("a", &[], &["out"])).unwrap();
+        graph.add_node(node("b", &[], &["out"])).unwrap();
+        graph
+            .add_node(
+                Node::new("merge", "test")
+                    .with_input(Port::new("items", "items", DataType::Any).multiple())
+                    .with_input(Port::new("single", "single", DataType::Any)),
+            )
+            .unwrap();
+
+        for from in ["a", "b"] {
+            graph
+                .add_connection(Connection::new(from, "out", "merge", "items"))
+                .unwrap();
+        }
+        graph
+            .add_connection(Connection::new("a", "out", "merge", "single"))
+            .unwrap();
+        let err = graph
+            .add_connection(Connection::new("b", "out", "merge", "single"))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DomainError::Validation(ValidationError::PortOverConnected { count: 2, .. })
+        ));
+        assert!(graph.validate().is_ok());
+
+        graph
+            .connections
+            .push(Connection::new("b", "out", "merge", "single"));
+        assert!(matches!(
+            graph.validate(),
+            Err(DomainError::Validation(
+                ValidationError::PortOverConnected { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn add_node_rejects_duplicate_id() {
+        let mut graph = NodeGraph::new();
+        graph.add_node(node("a", &[], &["out"])).unwrap();
+        let err = graph.add_node(node("a", &[], &["out"])).unwrap_err();
+        assert!(matches!(
+            err,
+            DomainError::Validation(ValidationError::DuplicateNodeId { .. })
+        ));
+    }
+
+    #[test]
+    fn add_node_rejects_duplicate_input_ports() {
+        let mut graph = NodeGraph::new();
+        let err = graph.add_node(node("a", &["in", "in"], &[])).unwrap_err();
+        assert!(matches!(
+            err,
+            DomainError::Validation(ValidationError::InvalidNodeConfig { .. })
+        ));
+        assert!(graph.nodes.is_empty());
+    }
+
+    #[test]
+    fn add_connection_requires_existing_ports() {
+        let mut graph = NodeGraph::new();
+        graph.add_node(node("a", &[], &["out"])).unwrap();
+        graph.add_node(node("b", &["in"], &[])).unwrap();
+        let err = graph
+            .add_connection(Connection::new("a", "missing", "b", "in"))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DomainError::Validation(ValidationError::PortNotFound { .. })
+        ));
+        graph
+            .add_connection(Connection::new("a", "out", "b", "in"))
+            .unwrap();
+        graph
+            .add_connection(Connection::new("a", "out", "b", "in"))
+            .unwrap();
+        assert_eq!(graph.connections.len(), 1);
+    }
+
+    #[test]
+    fn next_node_id_skips_taken_ids() {
+        let mut graph = NodeGraph::new();
+        assert_eq!(graph.next_node_id("http_request"), "http_request_1");
+        for id in ["http_request_1", "http_request_3"] {
+            graph.add_node(Node::new(id, "http_request")).unwrap();
+        }
+        assert_eq!(graph.next_node_id("http_request"), "http_request_2");
+        graph
+            .add_node(Node::new("http_request_2", "http_request"))
+            .unwrap();
+        assert_eq!(graph.next_node_id("http_request"), "http_request_4");
+    }
+
+    #[test]
+    fn remove_node_drops_its_connections() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.remove_node("b").unwrap();
+        assert!(graph.connections.is_empty());
+        assert!(graph.remove_node("b").is_err());
+    }
+
+    #[test]
+    fn sorted_views_are_stable_across_calls() {
+        let graph = chain_graph(
+            &["d", "b", "c", "a"],
+            &[("c", "d"), ("a", "c"), ("b", "d"), ("a", "b")],
+        );
+        let ids: Vec<&str> = graph.nodes_sorted().iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c", "d"]);
+        let keys: Vec<String> = graph.connections_sorted().iter().map(|c| c.key()).collect();
+        assert_eq!(
+            keys,
+            vec!["a.out->b.in", "a.out->c.in", "b.out->d.in", "c.out->d.in"]
+        );
+
+        assert_eq!(graph.nodes_sorted(), graph.nodes_sorted());
+        assert_eq!(graph.connections_sorted(), graph.connections_sorted());
+        assert_eq!(graph.connections[0].key(), "c.out->d.in");
+    }
+
+    #[test]
+    fn find_by_type_matches_only_that_type() {
+        let mut graph = NodeGraph::new();
+        for (id, node_type) in [
+            ("list", "http_request"),
+            ("title", "css_selector"),
+            ("detail", "http_request"),
+        ] {
+            graph.add_node(Node::new(id, node_type)).unwrap();
+        }
+
+        let ids: Vec<&str> = graph
+            .find_by_type("http_request")
+            .iter()
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["detail", "list"]);
+        assert!(graph.find_by_type("javascript").is_empty());
+
+        for node in graph.find_by_type_mut("http_request") {
+            node.set_config("url", json!("https://b.com"));
+        }
+        assert_eq!(graph.nodes["list"].config["url"], "https://b.com");
+        assert!(graph.nodes["title"].config.get("url").is_none());
+    }
+
+    #[test]
+    fn sort_connections_is_stable_and_idempotent() {
+        let mut graph = chain_graph(
+            &["a", "b", "c", "d"],
+            &[("c", "d"), ("a", "c"), ("b", "d"), ("a", "b")],
+        );
+        let original = graph.clone();
+
+        graph.sort_connections();
+        let keys: Vec<String> = graph.connections.iter().map(Connection::key).collect();
+        assert_eq!(
+            keys,
+            vec!["a.out->b.in", "a.out->c.in", "b.out->d.in", "c.out->d.in"]
+        );
+
+        let once = graph.clone();
+        graph.sort_connections();
+        assert_eq!(graph, once);
+
+        assert_eq!(graph.nodes, original.nodes);
+        assert_eq!(graph.connections.len(), original.connections.len());
+        assert!(original
+            .connections
+            .iter()
+            .all(|c| graph.connections.contains(c)));
+    }
+
+    #[test]
+    fn dependencies_and_dependents() {
+        let graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        assert_eq!(graph.get_dependencies("b"), vec!["a".to_string()]);
+        assert_eq!(graph.get_dependents("b"), vec!["c".to_string()]);
+        assert!(graph.get_dependencies("a").is_empty());
+    }
+}
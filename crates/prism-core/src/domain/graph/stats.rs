@@ -0,0 +1,139 @@
+//! 节点图统计
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::NodeGraph;
+use crate::domain::registry::{NodeCategory, NodeTypeRegistry};
+
+/// 编辑器状态栏展示的图概况
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct GraphStats {
+    pub node_count: usize,
+    pub connection_count: usize,
+    /// 已注册类型的节点按分类计数，没有节点的分类不出现
+    pub category_counts: BTreeMap<NodeCategory, usize>,
+    /// 类型未在注册表中的节点数
+    pub unknown_count: usize,
+    /// 入口节点（没有输入端口）数
+    pub entry_count: usize,
+    /// 出口节点（没有输出端口）数
+    pub exit_count: usize,
+    /// 图中是否没有环
+    pub is_dag: bool,
+}
+
+impl NodeGraph {
+    /// 汇总图的概况，节点分类通过注册表查询
+    pub fn stats(&self, registry: &NodeTypeRegistry) -> GraphStats {
+        let mut stats = GraphStats {
+            node_count: self.nodes.len(),
+            connection_count: self.connections.len(),
+            is_dag: self.detect_cycle().is_none(),
+            ..GraphStats::default()
+        };
+        for node in self.nodes.values() {
+            match registry.get(&node.node_type) {
+                Some(meta) => *stats.category_counts.entry(meta.category).or_insert(0) += 1,
+                None => stats.unknown_count += 1,
+            }
+            if node.inputs.is_empty() {
+                stats.entry_count += 1;
+            }
+            if node.outputs.is_empty() {
+                stats.exit_count += 1;
+            }
+        }
+        stats
+    }
+
+    /// 按节点分类统计节点数量
+    ///
+    /// 节点本身只记录类型 ID，分类通过注册表查询；未注册的类型计入
+    /// [`NodeCategory::Other`]。没有节点的分类不会出现在结果中。
+    pub fn category_breakdown(&self, registry: &NodeTypeRegistry) -> HashMap<NodeCategory, usize> {
+        let mut counts = HashMap::new();
+        for node in self.nodes.values() {
+            let category = registry
+                .get(&node.node_type)
+                .map(|m| m.category)
+                .unwrap_or(NodeCategory::Other);
+            *counts.entry(category).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Node, NodeTypeMetadata};
+
+    #[test]
+    fn stats_counts_unknown_types_separately() {
+        use crate::domain::graph::test_support::chain_graph;
+
+        let registry = NodeTypeRegistry::builder()
+            .register(NodeTypeMetadata::new(
+                "test",
+                "测试",
+                NodeCategory::Transform,
+            ))
+            .build();
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("a", "c")]);
+        graph.add_node(Node::new("custom", "acme.render")).unwrap();
+
+        let stats = graph.stats(&registry);
+        assert_eq!(stats.node_count, 4);
+        assert_eq!(stats.connection_count, 2);
+        assert_eq!(
+            stats.category_counts,
+            BTreeMap::from([(NodeCategory::Transform, 3)])
+        );
+        assert_eq!(stats.unknown_count, 1);
+        // custom 既没有输入也没有输出端口，同时计为入口和出口
+        assert_eq!(stats.entry_count, 2);
+        assert_eq!(stats.exit_count, 3);
+        assert!(stats.is_dag);
+    }
+
+    #[test]
+    fn category_breakdown_counts_per_category() {
+        let registry = NodeTypeRegistry::builder()
+            .register(NodeTypeMetadata::new(
+                "http_request",
+                "HTTP 请求",
+                NodeCategory::DataSource,
+            ))
+            .register(NodeTypeMetadata::new(
+                "constant",
+                "常量",
+                NodeCategory::DataSource,
+            ))
+            .register(NodeTypeMetadata::new(
+                "css_selector",
+                "CSS 选择器",
+                NodeCategory::Selector,
+            ))
+            .build();
+
+        let mut graph = NodeGraph::new();
+        for (id, node_type) in [
+            ("req", "http_request"),
+            ("const", "constant"),
+            ("sel1", "css_selector"),
+            ("sel2", "css_selector"),
+            ("custom", "acme.render"),
+        ] {
+            graph.add_node(Node::new(id, node_type)).unwrap();
+        }
+
+        let counts = graph.category_breakdown(&registry);
+        assert_eq!(counts[&NodeCategory::DataSource], 2);
+        assert_eq!(counts[&NodeCategory::Selector], 2);
+        assert_eq!(counts[&NodeCategory::Other], 1);
+        assert!(!counts.contains_key(&NodeCategory::Output));
+    }
+}
@@ -0,0 +1,389 @@
+//! 节点图校验
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::NodeGraph;
+use crate::domain::connection::Connection;
+use crate::domain::error::{Finding, Result, ValidationError};
+
+impl NodeGraph {
+    /// 校验整张图，返回遇到的第一个错误级别的问题，警告不影响结果
+    ///
+    /// 等价于取 [`NodeGraph::validate_all`] 中第一条 [`Severity::Error`](crate::domain::Severity::Error)。
+    pub fn validate(&self) -> Result<()> {
+        first_error(
+            self.validate_all()
+                .into_iter()
+                .filter(Finding::is_error)
+                .map(|f| f.error)
+                .collect(),
+        )
+    }
+
+    /// 校验整张图并收集所有问题，返回空列表表示没有任何问题
+    ///
+    /// 依次进行环检测（至多报告一个环）、端口连接校验与可达性检查（含没有数据来源的
+    /// 出口节点，见 [`NodeGraph::exit_nodes_reachable`]；已报告为
+    /// [`ValidationError::UnreachableNode`] 的出口不再重复报告）；相同的问题
+    /// 只报告一次，节点按 ID 顺序检查，结果顺序稳定。每条问题的严重程度取
+    /// [`ValidationError::severity`]。
+    pub fn validate_all(&self) -> Vec<Finding> {
+        let mut errors = Vec::new();
+        if let Some(path) = self.detect_cycle() {
+            errors.push(ValidationError::CycleDetected { path });
+        }
+        self.collect_port_errors(&mut errors);
+        let unreachable = self.unreachable_nodes();
+        errors.extend(
+            unreachable
+                .iter()
+                .map(|node_id| ValidationError::UnreachableNode {
+                    node_id: node_id.clone(),
+                }),
+        );
+        errors.extend(
+            self.exit_nodes_reachable()
+                .into_iter()
+                .filter(|node_id| !unreachable.contains(node_id))
+                .map(|node_id| ValidationError::ExitNotFed { node_id }),
+        );
+        errors.into_iter().map(Finding::from).collect()
+    }
+
+    /// 没有错误级别的问题，即图可以执行（可能带有警告）
+    pub fn is_runnable(&self) -> bool {
+        self.validate_all().iter().all(|f| !f.is_error())
+    }
+
+    /// 有输入端口但无法从任何入口节点（没有输入端口的节点）沿连接到达的节点，按 ID 排序
+    fn unreachable_nodes(&self) -> Vec<String> {
+        let mut reached: HashSet<&str> = self
+            .nodes
+            .values()
+            .filter(|n| n.inputs.is_empty())
+            .map(|n| n.id.as_str())
+            .collect();
+        let mut queue: VecDeque<&str> = reached.iter().copied().collect();
+        while let Some(id) = queue.pop_front() {
+            for c in self.connections.iter().filter(|c| c.from_node == id) {
+                if self.nodes.contains_key(&c.to_node) && reached.insert(c.to_node.as_str()) {
+                    queue.push_back(c.to_node.as_str());
+                }
+            }
+        }
+        let mut unreachable: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|id| !reached.contains(id.as_str()))
+            .cloned()
+            .collect();
+        unreachable.sort();
+        unreachable
+    }
+
+    /// 校验所有连接的端点存在、类型在图配置的兼容规则
+    /// （[`GraphConfig::type_compatibility`](super::GraphConfig::type_compatibility)）下兼容，
+    /// 必需的输入端口均已连接，且输入端口的连接数不超过其上限
+    pub fn validate_ports(&self) -> Result<()> {
+        let mut errors = Vec::new();
+        self.collect_port_errors(&mut errors);
+        first_error(errors)
+    }
+
+    fn collect_port_errors(&self, errors: &mut Vec<ValidationError>) {
+        let mut report = |error: ValidationError| {
+            if !errors.contains(&error) {
+                errors.push(error);
+            }
+        };
+
+        for conn in &self.connections {
+            for node_id in [&conn.from_node, &conn.to_node] {
+                if !self.nodes.contains_key(node_id) {
+                    report(ValidationError::NodeNotFound {
+                        node_id: node_id.clone(),
+                    });
+                }
+            }
+        }
+
+        let compatibility = self.config.type_compatibility();
+        for node in self.nodes_sorted() {
+            let mut incoming: HashMap<&str, Vec<&Connection>> = HashMap::new();
+            for c in self.connections.iter().filter(|c| c.to_node == node.id) {
+                incoming.entry(c.to_port.as_str()).or_default().push(c);
+            }
+
+            let mut target_ports: Vec<&str> = incoming.keys().copied().collect();
+            target_ports.sort_unstable();
+            for port_id in target_ports {
+                if node.get_input(port_id).is_none() {
+                    report(ValidationError::PortNotFound {
+                        node_id: node.id.clone(),
+                        port_id: port_id.to_string(),
+                    });
+                }
+            }
+
+            for port in &node.inputs {
+                let Some(conns) = incoming.get(port.id.as_str()) else {
+                    if port.required {
+                        report(ValidationError::PortNotConnected {
+                            node_id: node.id.clone(),
+                            port_id: port.id.clone(),
+                        });
+                    }
+                    continue;
+                };
+                if port
+                    .connection_limit()
+                    .is_some_and(|limit| conns.len() > limit)
+                {
+                    report(ValidationError::PortOverConnected {
+                        node_id: node.id.clone(),
+                        port_id: port.id.clone(),
+                        count: conns.len(),
+                    });
+                }
+
+                for conn in conns {
+                    // 来源节点缺失已在上面报告
+                    let Some(source) = self.nodes.get(&conn.from_node) else {
+                        continue;
+                    };
+                    let Some(from_port) = source.get_output(&conn.from_port) else {
+                        report(ValidationError::PortNotFound {
+                            node_id: conn.from_node.clone(),
+                            port_id: conn.from_port.clone(),
+                        });
+                        continue;
+                    };
+                    if !compatibility.is_compatible(from_port.data_type, port.data_type) {
+                        report(ValidationError::TypeMismatch {
+                            from_node: conn.from_node.clone(),
+                            from_port: conn.from_port.clone(),
+                            from_type: from_port.data_type,
+                            to_node: node.id.clone(),
+                            to_port: port.id.clone(),
+                            to_type: port.data_type,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn first_error(errors: Vec<ValidationError>) -> Result<()> {
+    match errors.into_iter().next() {
+        Some(error) => Err(error.into()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::graph::test_support::*;
+    use crate::domain::{
+        Connection, DataType, DomainError, Finding, Node, NodeGraph, Port, Severity, TypeCoercion,
+        TypeCompatibility, ValidationError,
+    };
+
+    fn typed_pair(from: DataType, to: DataType) -> NodeGraph {
+        let mut graph = NodeGraph::new();
+        graph
+            .add_node(Node::new("a", "test").with_output(Port::new("out", "out", from)))
+            .unwrap();
+        graph
+            .add_node(Node::new("b", "test").with_input(Port::new("in", "in", to)))
+            .unwrap();
+        graph
+            .add_connection(Connection::new("a", "out", "b", "in"))
+            .unwrap();
+        graph
+    }
+
+    #[test]
+    fn valid_chain_passes() {
+        let graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        assert!(graph.validate().is_ok());
+    }
+
+    #[test]
+    fn unconnected_required_port_fails() {
+        let mut graph = NodeGraph::new();
+        graph.add_node(node("a", &["in"], &[])).unwrap();
+        assert!(matches!(
+            graph.validate(),
+            Err(DomainError::Validation(
+                ValidationError::PortNotConnected { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn unconnected_optional_port_passes() {
+        let mut graph = NodeGraph::new();
+        graph
+            .add_node(
+                Node::new("a", "test").with_input(Port::new("in", "in", DataType::Any).optional()),
+            )
+            .unwrap();
+        assert!(graph.validate().is_ok());
+    }
+
+    #[test]
+    fn lenient_coercion_accepts_boolean_to_string() {
+        let mut graph = typed_pair(DataType::Boolean, DataType::String);
+        graph.config.type_coercion = Some(TypeCoercion::Strict);
+        assert!(graph.validate().is_err());
+        graph.config.type_coercion = Some(TypeCoercion::Lenient);
+        assert!(graph.validate().is_ok());
+    }
+
+    #[test]
+    fn custom_compatibility_rejects_default_coercion() {
+        let mut graph = typed_pair(DataType::Array, DataType::Object);
+        assert!(graph.validate().is_ok());
+        graph.config.type_compatibility =
+            Some(TypeCompatibility::default().forbid(DataType::Array, DataType::Object));
+        assert!(matches!(
+            graph.validate(),
+            Err(DomainError::Validation(
+                ValidationError::TypeMismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn validate_all_collects_every_problem() {
+        let mut graph = typed_pair(DataType::String, DataType::Number);
+        graph.add_node(node("c", &["in"], &[])).unwrap();
+        graph
+            .connections
+            .push(Connection::new("ghost", "out", "c", "in"));
+
+        let errors: Vec<_> = graph.validate_all().into_iter().map(|f| f.error).collect();
+        assert_eq!(errors.len(), 3);
+        assert!(
+            matches!(errors[0], ValidationError::NodeNotFound { ref node_id } if node_id == "ghost")
+        );
+        assert!(matches!(errors[1], ValidationError::TypeMismatch { .. }));
+        assert!(
+            matches!(errors[2], ValidationError::UnreachableNode { ref node_id } if node_id == "c")
+        );
+        assert!(matches!(
+            graph.validate(),
+            Err(DomainError::Validation(
+                ValidationError::NodeNotFound { .. }
+            ))
+        ));
+        assert!(chain_graph(&["a", "b"], &[("a", "b")])
+            .validate_all()
+            .is_empty());
+    }
+
+    #[test]
+    fn validate_all_reports_a_cycle_once() {
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c"), ("c", "a")]);
+        graph.add_node(node("d", &["in"], &[])).unwrap();
+        let errors: Vec<_> = graph.validate_all().into_iter().map(|f| f.error).collect();
+        let cycles = errors
+            .iter()
+            .filter(|e| matches!(e, ValidationError::CycleDetected { .. }))
+            .count();
+        assert_eq!(cycles, 1);
+        assert!(errors.contains(&ValidationError::PortNotConnected {
+            node_id: "d".into(),
+            port_id: "in".into(),
+        }));
+    }
+
+    #[test]
+    fn input_connection_count_respects_max_connections() {
+        let mut graph = chain_graph(&["a", "b", "c", "d"], &[]);
+        for id in ["a", "b", "c"] {
+            graph.get_node_mut(id).unwrap().outputs = node(id, &[], &["out"]).outputs;
+        }
+        let mut sink = Port::new("in", "in", DataType::Any);
+        sink.max_connections = Some(2);
+        graph.get_node_mut("d").unwrap().inputs = vec![sink];
+        for from in ["a", "b", "c"] {
+            graph
+                .connections
+                .push(Connection::new(from, "out", "d", "in"));
+        }
+        assert_eq!(
+            graph.validate_all(),
+            vec![Finding::from(ValidationError::PortOverConnected {
+                node_id: "d".into(),
+                port_id: "in".into(),
+                count: 3,
+            })]
+        );
+
+        graph.get_node_mut("d").unwrap().inputs[0].max_connections = None;
+        assert!(graph.validate().is_ok());
+    }
+
+    #[test]
+    fn unreachable_node_is_only_a_warning() {
+        let mut graph = chain_graph(&["a", "b"], &[("a", "b")]);
+        graph
+            .add_node(
+                Node::new("orphan", "test")
+                    .with_input(Port::new("in", "in", DataType::Any).optional()),
+            )
+            .unwrap();
+
+        assert_eq!(
+            graph.validate_all(),
+            vec![Finding {
+                severity: Severity::Warning,
+                error: ValidationError::UnreachableNode {
+                    node_id: "orphan".into()
+                },
+            }]
+        );
+        assert!(graph.is_runnable());
+        assert!(graph.validate().is_ok());
+
+        graph.get_node_mut("orphan").unwrap().inputs[0].required = true;
+        assert!(!graph.is_runnable());
+    }
+
+    #[test]
+    fn unfed_exit_is_reported_once() {
+        let mut graph = chain_graph(&["a", "b", "p", "q"], &[("a", "b"), ("p", "q")]);
+        graph.get_node_mut("p").unwrap().inputs = node("p", &["in"], &[]).inputs;
+        graph.get_node_mut("p").unwrap().inputs[0].required = false;
+
+        assert_eq!(graph.exit_nodes_reachable(), vec!["q"]);
+        let errors: Vec<_> = graph.validate_all().into_iter().map(|f| f.error).collect();
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::UnreachableNode {
+                    node_id: "p".into()
+                },
+                ValidationError::UnreachableNode {
+                    node_id: "q".into()
+                },
+            ]
+        );
+        assert!(graph.validate().is_ok());
+    }
+
+    #[test]
+    fn type_mismatch_fails() {
+        assert!(typed_pair(DataType::String, DataType::String)
+            .validate()
+            .is_ok());
+        assert!(matches!(
+            typed_pair(DataType::String, DataType::Number).validate(),
+            Err(DomainError::Validation(
+                ValidationError::TypeMismatch { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+//! 规则检查（lint）
+//!
+//! 与 [`CrawlerRule::validate`] 不同，lint 只产生警告：规则仍可保存和运行，但
+//! 可能存在遗漏或可疑的写法。较严格的检查需要通过 [`LintLevel`] 显式开启。
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::flow::FlowType;
+use super::rule::CrawlerRule;
+
+/// 检查级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// 只运行误报率低的检查
+    #[default]
+    Standard,
+    /// 额外运行可能有误报的检查，如从未连接的可选输入端口
+    Pedantic,
+}
+
+/// 一条检查警告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct LintWarning {
+    /// 机器可读的检查项标识，如 `unused_optional_input`
+    pub code: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flow_type: Option<FlowType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_id: Option<String>,
+}
+
+/// 一次检查的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct LintReport {
+    pub warnings: Vec<LintWarning>,
+}
+
+impl LintReport {
+    /// 是否没有任何警告
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+impl CrawlerRule {
+    /// 按指定级别检查规则，返回警告列表
+    pub fn lint(&self, level: LintLevel) -> LintReport {
+        let mut report = LintReport::default();
+        if level == LintLevel::Pedantic {
+            self.lint_unused_optional_inputs(&mut report);
+        }
+        report
+    }
+
+    /// 某节点类型的可选输入端口在整条规则的所有流程中从未被连接过，
+    /// 可能意味着对应功能漏接了线；对每个这样的节点实例报告一次
+    fn lint_unused_optional_inputs(&self, report: &mut LintReport) {
+        let flows = self.flows.defined_flows();
+        let connected: HashSet<(&str, &str)> = flows
+            .iter()
+            .flat_map(|(_, flow)| {
+                flow.graph.connections.iter().filter_map(|c| {
+                    let node = flow.graph.get_node(&c.to_node)?;
+                    Some((node.node_type.as_str(), c.to_port.as_str()))
+                })
+            })
+            .collect();
+
+        for (flow_type, flow) in flows {
+            for node in flow.graph.nodes_sorted() {
+                for port in node.inputs.iter().filter(|p| !p.required) {
+                    if connected.contains(&(node.node_type.as_str(), port.id.as_str())) {
+                        continue;
+                    }
+                    report.warnings.push(LintWarning {
+                        code: "unused_optional_input".to_string(),
+                        message: format!(
+                            "{}流程中节点 {} 的可选输入端口 {} 在整条规则中从未被连接",
+                            flow_type.display_name(),
+                            node.id,
+                            port.id
+                        ),
+                        flow_type: Some(flow_type),
+                        node_id: Some(node.id.clone()),
+                        port_id: Some(port.id.clone()),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Connection, DataType, Flow, Flows, MediaType, Node, NodeGraph, Port};
+
+    fn rule_with_optional_ports() -> CrawlerRule {
+        let mut graph = NodeGraph::new();
+        graph
+            .add_node(Node::new("src", "http_request").with_output(Port::new(
+                "body",
+                "body",
+                DataType::String,
+            )))
+            .unwrap();
+        graph
+            .add_node(
+                Node::new("parse", "css_selector")
+                    .with_input(Port::new("html", "html", DataType::String).optional())
+                    .with_input(Port::new("base_url", "base_url", DataType::String).optional()),
+            )
+            .unwrap();
+        graph
+            .add_connection(Connection::new("src", "body", "parse", "html"))
+            .unwrap();
+        CrawlerRule::new("测试", MediaType::Novel)
+            .with_flows(Flows::new().with_search(Flow::new(FlowType::Search, graph)))
+    }
+
+    #[test]
+    fn pedantic_reports_never_connected_optional_input() {
+        let report = rule_with_optional_ports().lint(LintLevel::Pedantic);
+        assert_eq!(report.warnings.len(), 1);
+        let warning = &report.warnings[0];
+        assert_eq!(warning.code, "unused_optional_input");
+        assert_eq!(warning.node_id.as_deref(), Some("parse"));
+        assert_eq!(warning.port_id.as_deref(), Some("base_url"));
+    }
+
+    #[test]
+    fn standard_level_skips_optional_input_check() {
+        assert!(rule_with_optional_ports()
+            .lint(LintLevel::Standard)
+            .is_clean());
+    }
+}
@@ -0,0 +1,140 @@
+//! 媒体类型
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 规则所抓取内容的媒体类型
+///
+/// 内置类型之外的分类用 [`MediaType::Other`] 表示，序列化为其中的字符串本身。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaType {
+    Video,
+    Music,
+    Novel,
+    Comic,
+    Image,
+    General,
+    /// 用户自定义的分类，如 `podcast`
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl MediaType {
+    /// 所有内置媒体类型，顺序稳定，供界面填充下拉列表
+    ///
+    /// 不包含 [`MediaType::Other`] 自定义分类。
+    pub fn all() -> &'static [MediaType] {
+        const ALL: &[MediaType] = &[
+            MediaType::Video,
+            MediaType::Music,
+            MediaType::Novel,
+            MediaType::Comic,
+            MediaType::Image,
+            MediaType::General,
+        ];
+        ALL
+    }
+
+    /// 界面显示名称，自定义分类直接显示其名称
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::Video => "视频",
+            Self::Music => "音乐",
+            Self::Novel => "小说",
+            Self::Comic => "漫画",
+            Self::Image => "图片",
+            Self::General => "通用",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// 序列化使用的字符串标识
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Video => "video",
+            Self::Music => "music",
+            Self::Novel => "novel",
+            Self::Comic => "comic",
+            Self::Image => "image",
+            Self::General => "general",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+/// 解析类型名称，忽略大小写并支持常见别名；其他名称作为 [`MediaType::Other`]
+/// 保留原样（去除两端空白），只有空字符串会报错
+impl FromStr for MediaType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "video" => Ok(Self::Video),
+            "music" | "audio" => Ok(Self::Music),
+            "novel" | "book" => Ok(Self::Novel),
+            "comic" | "manga" => Ok(Self::Comic),
+            "image" | "picture" => Ok(Self::Image),
+            "general" => Ok(Self::General),
+            "" => Err("媒体类型不能为空".to_string()),
+            _ => Ok(Self::Other(s.trim().to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_accepts_aliases() {
+        assert_eq!("Video".parse::<MediaType>(), Ok(MediaType::Video));
+        assert_eq!("manga".parse::<MediaType>(), Ok(MediaType::Comic));
+        assert_eq!(
+            " podcast ".parse::<MediaType>(),
+            Ok(MediaType::Other("podcast".into()))
+        );
+        assert!("  ".parse::<MediaType>().is_err());
+    }
+
+    #[test]
+    fn other_round_trips_as_plain_string() {
+        let podcast = MediaType::Other("podcast".into());
+        assert_eq!(serde_json::to_string(&podcast).unwrap(), "\"podcast\"");
+        assert_eq!(
+            serde_json::from_str::<MediaType>("\"podcast\"").unwrap(),
+            podcast
+        );
+        assert_eq!(
+            serde_json::from_str::<MediaType>("\"video\"").unwrap(),
+            MediaType::Video
+        );
+        assert_eq!(podcast.as_str(), "podcast");
+        assert_eq!(podcast.display_name(), "podcast");
+    }
+
+    #[test]
+    fn all_lists_builtin_types() {
+        let all = MediaType::all();
+        assert_eq!(all.len(), 6);
+        assert!(all.contains(&MediaType::General));
+        assert!(all.iter().all(|m| !matches!(m, MediaType::Other(_))));
+    }
+
+    #[test]
+    fn serializes_as_lowercase() {
+        assert_eq!(
+            serde_json::to_string(&MediaType::Novel).unwrap(),
+            "\"novel\""
+        );
+    }
+}
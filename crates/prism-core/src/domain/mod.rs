@@ -0,0 +1,32 @@
+//! 领域模型
+
+pub mod config;
+pub mod connection;
+pub mod error;
+pub mod flow;
+pub mod graph;
+pub mod lint;
+pub mod media;
+pub mod node;
+pub mod redact;
+pub mod registry;
+pub mod rule;
+pub mod types;
+
+pub use config::{
+    resolve_http, ConcurrencyConfig, GlobalConfig, HttpConfig, ProxyConfig, RetryPolicy,
+};
+pub use connection::Connection;
+pub use error::{DomainError, ErrorPayload, Finding, Result, Severity, ValidationError};
+pub use flow::{
+    Flow, FlowConfig, FlowParameter, FlowResult, FlowTemplate, FlowType, Flows, ParamType,
+};
+pub use graph::{GraphConfig, GraphDiff, GraphStats, NodeGraph};
+pub use lint::{LintLevel, LintReport, LintWarning};
+pub use media::MediaType;
+pub use node::{ErrorPolicy, Node, Port, Position};
+pub use registry::{
+    NodeCategory, NodeTypeMetadata, NodeTypeRegistry, NodeTypeRegistryBuilder, PortDef,
+};
+pub use rule::{CrawlerRule, FlowDiffSummary, Meta, RuleDiffSummary, CURRENT_SCHEMA_VERSION};
+pub use types::{is_type_compatible, DataType, TypeCoercion, TypeCompatibility};
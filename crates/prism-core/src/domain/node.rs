@@ -0,0 +1,449 @@
+//! 节点与端口
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+
+use super::error::{DomainError, Result, ValidationError};
+use super::redact::redact_secrets;
+use super::types::DataType;
+
+/// 节点配置中声明出错策略的保留键
+pub const ERROR_POLICY_KEY: &str = "_on_error";
+
+/// 节点执行失败时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorPolicy {
+    /// 终止整个流程（默认）
+    #[default]
+    Abort,
+    /// 记录错误并继续执行，下游依赖视为未满足
+    Skip,
+}
+
+/// 节点在编辑器画布上的坐标
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// 节点的输入或输出端口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct Port {
+    pub id: String,
+    pub name: String,
+    pub data_type: DataType,
+    /// 输入端口是否必须连接；对输出端口无意义
+    #[serde(default = "default_required")]
+    pub required: bool,
+    /// 输入端口是否接受任意多个连接（聚合、合并类节点）；对输出端口无意义
+    #[serde(default)]
+    pub multiple: bool,
+    /// 输入端口允许的最大连接数，`None` 表示不限；默认为 1。
+    /// 设置了 `multiple` 时忽略此项，见 [`Port::connection_limit`]
+    #[serde(default = "default_max_connections")]
+    pub max_connections: Option<usize>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+fn default_max_connections() -> Option<usize> {
+    Some(1)
+}
+
+impl Port {
+    /// 创建一个必需端口
+    pub fn new(id: impl Into<String>, name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            data_type,
+            required: true,
+            multiple: false,
+            max_connections: default_max_connections(),
+        }
+    }
+
+    /// 标记为可选端口
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    /// 实际生效的连接数上限：`multiple` 为真时不限，否则为 `max_connections`
+    pub fn connection_limit(&self) -> Option<usize> {
+        if self.multiple {
+            None
+        } else {
+            self.max_connections
+        }
+    }
+
+    /// 标记为可接受多个连接的输入端口
+    pub fn multiple(mut self) -> Self {
+        self.multiple = true;
+        self
+    }
+
+    /// 设置输入端口的连接数上限，`None` 表示不限
+    pub fn with_max_connections(mut self, max: Option<usize>) -> Self {
+        self.max_connections = max;
+        self
+    }
+}
+
+/// 节点图中的一个节点
+///
+/// `node_type` 对应节点类型注册表中的 `type_id`，`config` 为该类型的配置对象。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Node {
+    pub id: String,
+    pub node_type: String,
+    #[serde(default)]
+    pub inputs: Vec<Port>,
+    #[serde(default)]
+    pub outputs: Vec<Port>,
+    #[serde(default = "empty_config")]
+    pub config: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    /// 调试用：禁用的节点不参与执行
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+fn empty_config() -> Value {
+    Value::Object(Default::default())
+}
+
+impl Node {
+    pub fn new(id: impl Into<String>, node_type: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            node_type: node_type.into(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            config: empty_config(),
+            position: None,
+            disabled: false,
+        }
+    }
+
+    pub fn with_input(mut self, port: Port) -> Self {
+        self.inputs.push(port);
+        self
+    }
+
+    pub fn with_output(mut self, port: Port) -> Self {
+        self.outputs.push(port);
+        self
+    }
+
+    pub fn with_config(mut self, config: Value) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_position(mut self, x: f64, y: f64) -> Self {
+        self.position = Some(Position { x, y });
+        self
+    }
+
+    pub fn get_input(&self, port_id: &str) -> Option<&Port> {
+        self.inputs.iter().find(|p| p.id == port_id)
+    }
+
+    pub fn get_output(&self, port_id: &str) -> Option<&Port> {
+        self.outputs.iter().find(|p| p.id == port_id)
+    }
+
+    /// 读取配置项，缺失或类型不符时返回 `None`
+    pub fn get_config<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.config
+            .get(key)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+
+    /// 读取配置项并区分失败原因：缺失时返回 [`DomainError::NotFound`]，
+    /// 类型不符时返回 [`DomainError::Serialization`]
+    pub fn get_config_result<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
+        let value = self
+            .config
+            .get(key)
+            .ok_or_else(|| DomainError::NotFound(format!("节点 {} 的配置项 {key}", self.id)))?;
+        serde_json::from_value(value.clone()).map_err(|e| {
+            DomainError::Serialization(format!("节点 {} 的配置项 {key} 类型不符: {e}", self.id))
+        })
+    }
+
+    /// 读取必需的配置项，值为 `null` 时同样视为缺失
+    ///
+    /// 供节点执行器读取没有默认值的配置，错误同 [`Node::get_config_result`]。
+    pub fn require_config<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
+        if self.config.get(key).is_some_and(Value::is_null) {
+            return Err(DomainError::NotFound(format!(
+                "节点 {} 的必需配置项 {key}",
+                self.id
+            )));
+        }
+        self.get_config_result(key)
+    }
+
+    /// 节点的出错策略，读取配置键 `_on_error`（`"abort"` 或 `"skip"`），
+    /// 缺失或无法识别时为 [`ErrorPolicy::Abort`]
+    pub fn error_policy(&self) -> ErrorPolicy {
+        self.get_config(ERROR_POLICY_KEY).unwrap_or_default()
+    }
+
+    /// 脱敏后的配置副本，敏感字段的值显示为 `"***"`，用于日志与事件展示
+    ///
+    /// 敏感字段的判定见 [`redact`](super::redact) 模块，节点本身不受影响。
+    pub fn redacted_config(&self) -> Value {
+        redact_secrets(&self.config)
+    }
+
+    /// 修改输入端口 ID，端口不存在时返回 [`ValidationError::PortNotFound`]，
+    /// 新 ID 已被其他输入端口占用时返回 [`ValidationError::InvalidNodeConfig`]
+    ///
+    /// 只修改节点本身；图中引用该端口的连接需通过
+    /// [`NodeGraph::rename_port`](super::NodeGraph::rename_port) 同步改写。
+    pub fn rename_input_port(&mut self, old: &str, new: &str) -> Result<()> {
+        self.rename_port(true, old, new)
+    }
+
+    /// 修改输出端口 ID，规则同 [`Node::rename_input_port`]
+    pub fn rename_output_port(&mut self, old: &str, new: &str) -> Result<()> {
+        self.rename_port(false, old, new)
+    }
+
+    fn rename_port(&mut self, is_input: bool, old: &str, new: &str) -> Result<()> {
+        let ports = if is_input {
+            &self.inputs
+        } else {
+            &self.outputs
+        };
+        let Some(index) = ports.iter().position(|p| p.id == old) else {
+            return Err(ValidationError::PortNotFound {
+                node_id: self.id.clone(),
+                port_id: old.to_string(),
+            }
+            .into());
+        };
+        if old != new && ports.iter().any(|p| p.id == new) {
+            return Err(ValidationError::InvalidNodeConfig {
+                node_id: self.id.clone(),
+                node_type: self.node_type.clone(),
+                reason: format!("端口 ID 重复: {new}"),
+            }
+            .into());
+        }
+        let ports = if is_input {
+            &mut self.inputs
+        } else {
+            &mut self.outputs
+        };
+        ports[index].id = new.to_string();
+        Ok(())
+    }
+
+    /// 检查节点自身的结构：`id` 与 `node_type` 非空，输入端口 ID 互不相同，
+    /// 输出端口 ID 互不相同
+    ///
+    /// 重复的端口 ID 会让 [`Node::get_input`] / [`Node::get_output`] 的结果含糊，
+    /// 连接无法确定落在哪个端口上。失败时返回 [`ValidationError::InvalidNodeConfig`]。
+    pub fn validate(&self) -> std::result::Result<(), ValidationError> {
+        let invalid = |reason: String| ValidationError::InvalidNodeConfig {
+            node_id: self.id.clone(),
+            node_type: self.node_type.clone(),
+            reason,
+        };
+        if self.id.trim().is_empty() {
+            return Err(invalid("节点 ID 不能为空".into()));
+        }
+        if self.node_type.trim().is_empty() {
+            return Err(invalid("节点类型不能为空".into()));
+        }
+        for (kind, ports) in [("输入", &self.inputs), ("输出", &self.outputs)] {
+            for (i, port) in ports.iter().enumerate() {
+                if ports[..i].iter().any(|p| p.id == port.id) {
+                    return Err(invalid(format!("{kind}端口 ID 重复: {}", port.id)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 写入配置项；若当前配置不是对象则先重置为空对象
+    pub fn set_config(&mut self, key: impl Into<String>, value: Value) {
+        if !self.config.is_object() {
+            self.config = empty_config();
+        }
+        if let Value::Object(map) = &mut self.config {
+            map.insert(key.into(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn get_config_returns_typed_value() {
+        let node = Node::new("n1", "http_request").with_config(json!({ "url": "https://a.com" }));
+        assert_eq!(
+            node.get_config::<String>("url").as_deref(),
+            Some("https://a.com")
+        );
+        assert_eq!(node.get_config::<u32>("url"), None);
+        assert_eq!(node.get_config::<String>("missing"), None);
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_ports_and_empty_ids() {
+        let port = |id: &str| Port::new(id, id, DataType::Any);
+        let node = Node::new("n1", "merge")
+            .with_input(port("a"))
+            .with_input(port("a"))
+            .with_output(port("a"));
+        let err = node.validate().unwrap_err();
+        assert!(
+            matches!(&err, ValidationError::InvalidNodeConfig { reason, .. } if reason.contains("输入端口 ID 重复"))
+        );
+
+        let node = Node::new("n1", "merge")
+            .with_input(port("a"))
+            .with_output(port("a"));
+        assert_eq!(node.validate(), Ok(()));
+        assert!(Node::new("", "merge").validate().is_err());
+        assert!(Node::new("n1", " ").validate().is_err());
+    }
+
+    #[test]
+    fn get_config_result_distinguishes_missing_and_wrong_type() {
+        let node = Node::new("n1", "http_request")
+            .with_config(json!({ "url": "https://a.com", "timeout": "soon", "proxy": null }));
+        assert_eq!(
+            node.get_config_result::<String>("url").unwrap(),
+            "https://a.com"
+        );
+        assert!(matches!(
+            node.get_config_result::<u64>("timeout"),
+            Err(DomainError::Serialization(_))
+        ));
+        assert!(matches!(
+            node.get_config_result::<String>("method"),
+            Err(DomainError::NotFound(_))
+        ));
+
+        assert_eq!(
+            node.get_config_result::<Option<String>>("proxy").unwrap(),
+            None
+        );
+        assert!(matches!(
+            node.require_config::<Option<String>>("proxy"),
+            Err(DomainError::NotFound(_))
+        ));
+        assert_eq!(
+            node.require_config::<String>("url").unwrap(),
+            "https://a.com"
+        );
+    }
+
+    #[test]
+    fn rename_port_checks_existence_and_collisions() {
+        let mut node = Node::new("n1", "merge")
+            .with_input(Port::new("a", "a", DataType::Any))
+            .with_input(Port::new("b", "b", DataType::Any))
+            .with_output(Port::new("a", "a", DataType::Any));
+        node.rename_input_port("a", "c").unwrap();
+        assert!(node.get_input("c").is_some() && node.get_input("a").is_none());
+        assert!(node.get_output("a").is_some());
+
+        assert!(matches!(
+            node.rename_output_port("missing", "x"),
+            Err(DomainError::Validation(
+                ValidationError::PortNotFound { .. }
+            ))
+        ));
+        assert!(matches!(
+            node.rename_input_port("c", "b"),
+            Err(DomainError::Validation(
+                ValidationError::InvalidNodeConfig { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn set_config_inserts_key() {
+        let mut node = Node::new("n1", "constant");
+        node.set_config("value", json!(42));
+        assert_eq!(node.get_config::<i64>("value"), Some(42));
+    }
+
+    #[test]
+    fn redacted_config_masks_secrets_only() {
+        let node = Node::new("login", "http_request")
+            .with_config(json!({ "url": "https://a.com/login", "password": "hunter2" }));
+        assert_eq!(
+            node.redacted_config(),
+            json!({ "url": "https://a.com/login", "password": "***" })
+        );
+        assert_eq!(node.config["password"], json!("hunter2"));
+    }
+
+    #[test]
+    fn error_policy_defaults_to_abort() {
+        let node = Node::new("n1", "http_request");
+        assert_eq!(node.error_policy(), ErrorPolicy::Abort);
+
+        let node = node.with_config(json!({ "_on_error": "skip" }));
+        assert_eq!(node.error_policy(), ErrorPolicy::Skip);
+
+        let node = Node::new("n1", "http_request").with_config(json!({ "_on_error": "retry" }));
+        assert_eq!(node.error_policy(), ErrorPolicy::Abort);
+    }
+
+    #[test]
+    fn deserializes_with_defaults() {
+        let node: Node =
+            serde_json::from_value(json!({ "id": "n1", "node_type": "constant" })).unwrap();
+        assert!(node.inputs.is_empty());
+        assert_eq!(node.config, json!({}));
+        assert!(!node.disabled);
+    }
+
+    #[test]
+    fn with_max_connections_sets_limit() {
+        let port = Port::new("in", "in", DataType::Any);
+        assert_eq!(port.connection_limit(), Some(1));
+        let unlimited = port.clone().with_max_connections(None);
+        assert_eq!(unlimited.connection_limit(), None);
+        assert!(!unlimited.multiple);
+        assert_eq!(
+            port.with_max_connections(Some(3)).connection_limit(),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn port_max_connections_defaults_to_one() {
+        let port: Port =
+            serde_json::from_value(json!({ "id": "in", "name": "in", "data_type": "string" }))
+                .unwrap();
+        assert_eq!(port.max_connections, Some(1));
+        assert_eq!(port.connection_limit(), Some(1));
+
+        let port: Port = serde_json::from_value(
+            json!({ "id": "in", "name": "in", "data_type": "string", "max_connections": null }),
+        )
+        .unwrap();
+        assert_eq!(port.connection_limit(), None);
+    }
+}
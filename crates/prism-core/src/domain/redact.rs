@@ -0,0 +1,81 @@
+//! 敏感配置脱敏
+//!
+//! 日志、事件流等对外展示配置时，用 [`REDACTED`] 替换看起来像凭据的字段值。
+//! 判断只依据键名，按小写后是否包含常见的凭据关键词，宁可多遮也不漏遮。
+
+use serde_json::Value;
+
+/// 脱敏后的占位值
+pub const REDACTED: &str = "***";
+
+/// 键名中出现即视为敏感的关键词（小写，`-` 视同 `_`）
+const SECRET_KEY_MARKERS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "access_key",
+    "private_key",
+    "credential",
+    "authorization",
+    "cookie",
+    "session",
+];
+
+/// 键名是否看起来对应敏感值
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase().replace('-', "_");
+    SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+/// 返回 `value` 的副本，其中所有敏感键（任意嵌套层级）的值被替换为 [`REDACTED`]
+pub fn redact_secrets(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, v)| {
+                    let v = if is_secret_key(key) {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_secrets(v)
+                    };
+                    (key.clone(), v)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_secrets).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn secret_keys_match_case_insensitively() {
+        assert!(is_secret_key("Password"));
+        assert!(is_secret_key("X-Api-Key"));
+        assert!(is_secret_key("refresh_token"));
+        assert!(!is_secret_key("url"));
+        assert!(!is_secret_key("selector"));
+    }
+
+    #[test]
+    fn redacts_nested_objects_and_arrays() {
+        let value = json!({
+            "headers": { "Authorization": "Bearer x", "Accept": "*/*" },
+            "accounts": [{ "user": "a", "password": "p" }],
+        });
+        assert_eq!(
+            redact_secrets(&value),
+            json!({
+                "headers": { "Authorization": "***", "Accept": "*/*" },
+                "accounts": [{ "user": "a", "password": "***" }],
+            })
+        );
+    }
+}
@@ -0,0 +1,940 @@
+//! 节点类型注册表
+//!
+//! 注册表描述编辑器中可用的节点类型：所属分类、默认端口与配置的 JSON Schema。
+//!
+//! # 类型 ID 规范
+//!
+//! `type_id` 在注册时规范化为小写，只允许 ASCII 字母、数字以及 `_`、`-`、`.`，
+//! 不能为空或包含空白。因此 `HttpRequest` 与 `httprequest` 视为同一类型，
+//! 查询时同样忽略大小写。推荐使用 `snake_case`，节点包可用 `.` 作为命名空间
+//! 分隔符（如 `acme.render_page`）。
+
+mod builtin;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+
+use super::error::{DomainError, ValidationError};
+use super::graph::NodeGraph;
+use super::node::{Node, Port};
+use super::types::DataType;
+
+/// 节点分类
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeCategory {
+    DataSource,
+    Selector,
+    Filter,
+    Transform,
+    Control,
+    Script,
+    Output,
+    Other,
+}
+
+impl NodeCategory {
+    /// 所有分类，按节点面板中的显示顺序
+    pub fn all() -> &'static [NodeCategory] {
+        &[
+            Self::DataSource,
+            Self::Selector,
+            Self::Filter,
+            Self::Transform,
+            Self::Control,
+            Self::Script,
+            Self::Output,
+            Self::Other,
+        ]
+    }
+
+    /// 界面显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::DataSource => "数据源",
+            Self::Selector => "选择器",
+            Self::Filter => "过滤",
+            Self::Transform => "转换",
+            Self::Control => "控制",
+            Self::Script => "脚本",
+            Self::Output => "输出",
+            Self::Other => "其他",
+        }
+    }
+}
+
+impl fmt::Display for NodeCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+/// 节点类型声明的端口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PortDef {
+    pub id: String,
+    pub name: String,
+    pub data_type: DataType,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    /// 输入端口是否接受多个连接，节点包可借此声明自己的聚合节点
+    #[serde(default)]
+    pub multiple: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl PortDef {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            data_type,
+            required: true,
+            multiple: false,
+            description: None,
+        }
+    }
+
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    pub fn multiple(mut self) -> Self {
+        self.multiple = true;
+        self
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// 转换为节点实例上的端口
+    pub fn to_port(&self) -> Port {
+        let mut port = Port::new(self.id.clone(), self.name.clone(), self.data_type);
+        port.required = self.required;
+        port.multiple = self.multiple;
+        port
+    }
+}
+
+/// 节点类型元数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct NodeTypeMetadata {
+    pub type_id: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub description: String,
+    pub category: NodeCategory,
+    #[serde(default)]
+    pub inputs: Vec<PortDef>,
+    #[serde(default)]
+    pub outputs: Vec<PortDef>,
+    /// 节点 `config` 的 JSON Schema
+    #[serde(default = "empty_schema")]
+    pub config_schema: Value,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub deprecated: bool,
+    /// 全局同时执行该类型节点的上限，如浏览器渲染这类重量级节点；`None` 或 `0` 表示不限
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_global_concurrency: Option<usize>,
+}
+
+fn empty_schema() -> Value {
+    serde_json::json!({ "type": "object" })
+}
+
+impl NodeTypeMetadata {
+    pub fn new(
+        type_id: impl Into<String>,
+        display_name: impl Into<String>,
+        category: NodeCategory,
+    ) -> Self {
+        Self {
+            type_id: type_id.into(),
+            display_name: display_name.into(),
+            description: String::new(),
+            category,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            config_schema: empty_schema(),
+            tags: Vec::new(),
+            deprecated: false,
+            max_global_concurrency: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn with_input(mut self, port: PortDef) -> Self {
+        self.inputs.push(port);
+        self
+    }
+
+    pub fn with_output(mut self, port: PortDef) -> Self {
+        self.outputs.push(port);
+        self
+    }
+
+    pub fn with_config_schema(mut self, schema: Value) -> Self {
+        self.config_schema = schema;
+        self
+    }
+
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
+    /// `0` 视为不限，见 [`NodeTypeMetadata::max_global_concurrency`]
+    pub fn with_max_global_concurrency(mut self, limit: usize) -> Self {
+        self.max_global_concurrency = Some(limit);
+        self
+    }
+
+    /// 用 `config_schema` 顶层属性的 `default` 补齐 `config` 中缺失的配置项
+    ///
+    /// 已有的配置项保持不变；`config` 不是对象时先重置为空对象。
+    /// 目前只处理顶层属性，不会深入嵌套对象。
+    pub fn apply_config_defaults(&self, config: &mut Value) {
+        let Some(properties) = self
+            .config_schema
+            .get("properties")
+            .and_then(Value::as_object)
+        else {
+            return;
+        };
+        if !config.is_object() {
+            *config = Value::Object(Default::default());
+        }
+        let Value::Object(map) = config else {
+            return;
+        };
+        for (key, property) in properties {
+            if let Some(default) = property.get("default") {
+                map.entry(key.clone()).or_insert_with(|| default.clone());
+            }
+        }
+    }
+
+    /// 按该类型的端口声明创建节点实例，配置填入 Schema 中的默认值
+    pub fn create_node(&self, id: impl Into<String>) -> Node {
+        let mut node = Node::new(id, self.type_id.clone());
+        node.inputs = self.inputs.iter().map(PortDef::to_port).collect();
+        node.outputs = self.outputs.iter().map(PortDef::to_port).collect();
+        self.apply_config_defaults(&mut node.config);
+        node
+    }
+}
+
+/// 校验并规范化类型 ID，见模块文档中的规范
+pub fn normalize_type_id(type_id: &str) -> Result<String, String> {
+    if type_id.is_empty() {
+        return Err("节点类型 ID 不能为空".to_string());
+    }
+    if let Some(c) = type_id
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+    {
+        return Err(format!("节点类型 ID 包含非法字符 {c:?}: {type_id:?}"));
+    }
+    Ok(type_id.to_ascii_lowercase())
+}
+
+/// 节点类型注册表
+#[derive(Debug, Clone, Default)]
+pub struct NodeTypeRegistry {
+    types: HashMap<String, NodeTypeMetadata>,
+    by_category: HashMap<NodeCategory, Vec<String>>,
+}
+
+impl NodeTypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> NodeTypeRegistryBuilder {
+        NodeTypeRegistryBuilder::default()
+    }
+
+    /// 从目录中的 `*.json` 文件加载节点类型，每个文件是一个 [`NodeTypeMetadata`]
+    ///
+    /// 供高级用户在不重新编译的情况下添加自定义节点。文件按文件名顺序注册，
+    /// 其他扩展名的文件被忽略，空目录得到空注册表。目录不存在时返回
+    /// [`DomainError::NotFound`]，其他读取失败（如无权限、不是目录）返回
+    /// [`DomainError::Storage`]。任一文件解析失败或类型 ID 重复时返回
+    /// [`DomainError::Serialization`]，其中列出所有出错的文件。
+    pub fn from_dir(path: &Path) -> crate::domain::Result<Self> {
+        let read_error = |e: std::io::Error| {
+            let message = format!("无法读取节点类型目录 {}: {e}", path.display());
+            if e.kind() == std::io::ErrorKind::NotFound {
+                DomainError::NotFound(message)
+            } else {
+                DomainError::Storage(message)
+            }
+        };
+        let mut files: Vec<_> = std::fs::read_dir(path)
+            .map_err(read_error)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<_>>()
+            .map_err(read_error)?;
+        files.retain(|file| {
+            file.is_file()
+                && file
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        });
+        files.sort();
+
+        let mut registry = Self::new();
+        let mut failures = Vec::new();
+        for file in files {
+            let name = file
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let result = std::fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|text| {
+                    serde_json::from_str::<NodeTypeMetadata>(&text).map_err(|e| e.to_string())
+                })
+                .and_then(|metadata| registry.register(metadata));
+            if let Err(e) = result {
+                failures.push(format!("{name}: {e}"));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(registry)
+        } else {
+            Err(DomainError::Serialization(format!(
+                "加载节点类型失败: {}",
+                failures.join("; ")
+            )))
+        }
+    }
+
+    /// 导出全部节点类型，供前端渲染节点面板
+    ///
+    /// 结果是 [`NodeTypeMetadata`] 数组，按分类（面板显示顺序）再按 `type_id` 排序，
+    /// 可由 [`NodeTypeRegistry::from_json`] 还原。
+    pub fn to_json(&self) -> Value {
+        let mut types = self.all();
+        types.sort_by(|a, b| (a.category, &a.type_id).cmp(&(b.category, &b.type_id)));
+        serde_json::to_value(types).expect("节点类型元数据总能序列化为 JSON")
+    }
+
+    /// 从 [`NodeTypeRegistry::to_json`] 导出的数组重建注册表
+    ///
+    /// 结构不符、类型 ID 非法或重复（忽略大小写）时返回 [`DomainError::Serialization`]。
+    pub fn from_json(value: Value) -> crate::domain::Result<Self> {
+        let types: Vec<NodeTypeMetadata> = serde_json::from_value(value)
+            .map_err(|e| DomainError::Serialization(format!("节点类型列表格式错误: {e}")))?;
+        let mut registry = Self::new();
+        for metadata in types {
+            registry
+                .register(metadata)
+                .map_err(|e| DomainError::Serialization(format!("导入节点类型失败: {e}")))?;
+        }
+        Ok(registry)
+    }
+
+    /// 注册节点类型
+    ///
+    /// `type_id` 会被规范化；非法 ID 或与已注册类型（忽略大小写）重复时返回错误。
+    pub fn register(&mut self, mut metadata: NodeTypeMetadata) -> Result<(), String> {
+        let type_id = normalize_type_id(&metadata.type_id)?;
+        if self.types.contains_key(&type_id) {
+            return Err(format!("节点类型已注册: {type_id}"));
+        }
+        metadata.type_id = type_id.clone();
+        self.by_category
+            .entry(metadata.category)
+            .or_default()
+            .push(type_id.clone());
+        self.types.insert(type_id, metadata);
+        Ok(())
+    }
+
+    /// 移除节点类型（忽略大小写），同时从分类索引中删除；分类因此变空时连同分类一起移除
+    pub fn unregister(&mut self, type_id: &str) -> Option<NodeTypeMetadata> {
+        let metadata = self.types.remove(&type_id.to_ascii_lowercase())?;
+        if let Some(ids) = self.by_category.get_mut(&metadata.category) {
+            ids.retain(|id| *id != metadata.type_id);
+            if ids.is_empty() {
+                self.by_category.remove(&metadata.category);
+            }
+        }
+        Some(metadata)
+    }
+
+    /// 注册节点类型，已存在同 ID 的类型时先将其移除，返回被替换的旧定义
+    ///
+    /// 用于热重载自定义节点。ID 非法时返回错误且不修改注册表。
+    pub fn replace(
+        &mut self,
+        metadata: NodeTypeMetadata,
+    ) -> Result<Option<NodeTypeMetadata>, String> {
+        let type_id = normalize_type_id(&metadata.type_id)?;
+        let previous = self.unregister(&type_id);
+        self.register(metadata)?;
+        Ok(previous)
+    }
+
+    /// 按类型 ID 查找（忽略大小写）
+    pub fn get(&self, type_id: &str) -> Option<&NodeTypeMetadata> {
+        self.types.get(&type_id.to_ascii_lowercase())
+    }
+
+    pub fn contains(&self, type_id: &str) -> bool {
+        self.get(type_id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    pub fn all(&self) -> Vec<&NodeTypeMetadata> {
+        self.types.values().collect()
+    }
+
+    /// 指定分类下的节点类型，按注册顺序
+    pub fn by_category(&self, category: NodeCategory) -> Vec<&NodeTypeMetadata> {
+        self.by_category
+            .get(&category)
+            .map(|ids| ids.iter().filter_map(|id| self.types.get(id)).collect())
+            .unwrap_or_default()
+    }
+
+    /// 带有指定标签的节点类型（标签忽略大小写），按类型 ID 排序，包含已弃用类型
+    pub fn by_tag(&self, tag: &str) -> Vec<&NodeTypeMetadata> {
+        let mut types: Vec<_> = self
+            .types
+            .values()
+            .filter(|m| m.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            .collect();
+        types.sort_by(|a, b| a.type_id.cmp(&b.type_id));
+        types
+    }
+
+    /// 未弃用的节点类型，按类型 ID 排序
+    ///
+    /// 供节点面板默认隐藏已弃用类型；加载旧图时仍可通过 [`NodeTypeRegistry::get`] 取得它们。
+    pub fn non_deprecated(&self) -> Vec<&NodeTypeMetadata> {
+        let mut types: Vec<_> = self.types.values().filter(|m| !m.deprecated).collect();
+        types.sort_by(|a, b| a.type_id.cmp(&b.type_id));
+        types
+    }
+
+    /// 按节点类型的 `config_schema` 校验节点配置
+    ///
+    /// 失败时返回 [`ValidationError::InvalidNodeConfig`]，`reason` 为第一处违反
+    /// Schema 的位置与描述；类型未注册或 Schema 本身无效同样视为配置无效。
+    pub fn validate_node_config(&self, node: &Node) -> Result<(), ValidationError> {
+        let invalid = |reason: String| ValidationError::InvalidNodeConfig {
+            node_id: node.id.clone(),
+            node_type: node.node_type.clone(),
+            reason,
+        };
+        let metadata = self
+            .get(&node.node_type)
+            .ok_or_else(|| invalid("unknown node type".to_string()))?;
+        let validator = jsonschema::validator_for(&metadata.config_schema)
+            .map_err(|e| invalid(format!("配置 Schema 无效: {e}")))?;
+        validator.validate(&node.config).map_err(|e| {
+            let path = e.instance_path().as_str();
+            if path.is_empty() {
+                invalid(e.to_string())
+            } else {
+                invalid(format!("{path}: {e}"))
+            }
+        })
+    }
+
+    /// 校验图中所有节点的配置，返回全部问题（按节点 ID 排序），空列表表示都合法
+    pub fn validate_graph_configs(&self, graph: &NodeGraph) -> Vec<ValidationError> {
+        graph
+            .nodes_sorted()
+            .into_iter()
+            .filter_map(|node| self.validate_node_config(node).err())
+            .collect()
+    }
+
+    /// 在类型 ID、名称、描述与标签中搜索（忽略大小写），按相关度排序
+    ///
+    /// 排序规则见 [`NodeTypeRegistry::search_ranked`]。
+    pub fn search(&self, query: &str) -> Vec<&NodeTypeMetadata> {
+        self.search_ranked(query)
+            .into_iter()
+            .map(|(metadata, _)| metadata)
+            .collect()
+    }
+
+    /// 带相关度分数的搜索结果，分数越高越靠前（忽略大小写）
+    ///
+    /// | 匹配方式 | 分数 |
+    /// | --- | --- |
+    /// | `type_id` 完全相同 | 1.0 |
+    /// | `display_name` 以查询开头 | 0.8 |
+    /// | `type_id` 或 `display_name` 包含查询 | 0.6 |
+    /// | 描述或标签包含查询 | 0.3 |
+    ///
+    /// 取最高的一项作为节点分数；分数相同时按 `type_id` 排序。
+    pub fn search_ranked(&self, query: &str) -> Vec<(&NodeTypeMetadata, f32)> {
+        let query = query.to_lowercase();
+        let mut results: Vec<(&NodeTypeMetadata, f32)> = self
+            .types
+            .values()
+            .filter_map(|m| {
+                let name = m.display_name.to_lowercase();
+                let score = if m.type_id == query {
+                    1.0
+                } else if name.starts_with(&query) {
+                    0.8
+                } else if m.type_id.contains(&query) || name.contains(&query) {
+                    0.6
+                } else if m.description.to_lowercase().contains(&query)
+                    || m.tags.iter().any(|t| t.to_lowercase().contains(&query))
+                {
+                    0.3
+                } else {
+                    return None;
+                };
+                Some((m, score))
+            })
+            .collect();
+        results.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| a.type_id.cmp(&b.type_id))
+        });
+        results
+    }
+}
+
+/// 以链式调用构建注册表
+#[derive(Debug, Default)]
+pub struct NodeTypeRegistryBuilder {
+    registry: NodeTypeRegistry,
+}
+
+impl NodeTypeRegistryBuilder {
+    /// 注册节点类型；ID 非法或重复时记录警告并跳过该类型
+    ///
+    /// 适用于内置的类型列表。类型来自用户数据时使用
+    /// [`NodeTypeRegistryBuilder::try_register`]。
+    pub fn register(mut self, metadata: NodeTypeMetadata) -> Self {
+        let type_id = metadata.type_id.clone();
+        if let Err(e) = self.registry.register(metadata) {
+            tracing::warn!(type_id = %type_id, error = %e, "跳过无法注册的节点类型");
+        }
+        self
+    }
+
+    /// 注册节点类型，ID 非法或重复时返回错误
+    pub fn try_register(mut self, metadata: NodeTypeMetadata) -> Result<Self, String> {
+        self.registry.register(metadata)?;
+        Ok(self)
+    }
+
+    pub fn build(self) -> NodeTypeRegistry {
+        self.registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 只有一个字符串属性的配置 Schema，带默认值
+    fn string_config_schema(key: &str, default: &str) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                key: { "type": "string", "default": default }
+            }
+        })
+    }
+
+    fn metadata(type_id: &str, category: NodeCategory) -> NodeTypeMetadata {
+        NodeTypeMetadata::new(type_id, type_id, category)
+            .with_config_schema(string_config_schema("value", ""))
+    }
+
+    fn write_metadata(dir: &Path, file: &str, type_id: &str) {
+        let meta = metadata(type_id, NodeCategory::Transform);
+        std::fs::write(dir.join(file), serde_json::to_string(&meta).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn json_export_round_trips_in_palette_order() {
+        let registry = NodeTypeRegistry::builder()
+            .register(metadata("output_b", NodeCategory::Output))
+            .register(metadata("fetch", NodeCategory::DataSource))
+            .register(metadata("output_a", NodeCategory::Output))
+            .build();
+        let value = registry.to_json();
+        let ids: Vec<&str> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["type_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["fetch", "output_a", "output_b"]);
+
+        let restored = NodeTypeRegistry::from_json(value.clone()).unwrap();
+        assert_eq!(restored.to_json(), value);
+
+        let duplicated = json!([value[0], value[0]]);
+        assert!(matches!(
+            NodeTypeRegistry::from_json(duplicated),
+            Err(DomainError::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn from_dir_loads_json_files_and_ignores_others() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(NodeTypeRegistry::from_dir(dir.path()).unwrap().is_empty());
+
+        write_metadata(dir.path(), "trim.json", "trim");
+        write_metadata(dir.path(), "upper.JSON", "upper");
+        std::fs::write(dir.path().join("README.md"), "# 自定义节点").unwrap();
+
+        let registry = NodeTypeRegistry::from_dir(dir.path()).unwrap();
+        assert_eq!(registry.len(), 2);
+        assert!(registry.contains("trim") && registry.contains("upper"));
+    }
+
+    #[test]
+    fn from_dir_maps_io_errors_by_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            NodeTypeRegistry::from_dir(&dir.path().join("missing")),
+            Err(DomainError::NotFound(_))
+        ));
+
+        let file = dir.path().join("nodes.json");
+        std::fs::write(&file, "{}").unwrap();
+        assert!(matches!(
+            NodeTypeRegistry::from_dir(&file),
+            Err(DomainError::Storage(_))
+        ));
+    }
+
+    #[test]
+    fn from_dir_lists_malformed_and_duplicate_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_metadata(dir.path(), "a.json", "trim");
+        write_metadata(dir.path(), "b.json", "upper");
+        write_metadata(dir.path(), "c.json", "Trim");
+        std::fs::write(dir.path().join("broken.json"), "{ not json").unwrap();
+
+        let err = NodeTypeRegistry::from_dir(dir.path()).unwrap_err();
+        let DomainError::Serialization(message) = err else {
+            panic!("应为 Serialization 错误");
+        };
+        assert!(message.contains("broken.json"), "{message}");
+        assert!(message.contains("c.json"), "{message}");
+        assert!(!message.contains("a.json"), "{message}");
+    }
+
+    #[test]
+    fn apply_config_defaults_fills_missing_keys_only() {
+        let meta = NodeTypeMetadata::new("http_request", "HTTP 请求", NodeCategory::DataSource)
+            .with_config_schema(string_config_schema("method", "GET"));
+
+        let mut config = json!({});
+        meta.apply_config_defaults(&mut config);
+        assert_eq!(config, json!({ "method": "GET" }));
+
+        let mut config = json!({ "method": "POST", "url": "https://a.com" });
+        meta.apply_config_defaults(&mut config);
+        assert_eq!(config, json!({ "method": "POST", "url": "https://a.com" }));
+
+        let mut config = Value::Null;
+        meta.apply_config_defaults(&mut config);
+        assert_eq!(config, json!({ "method": "GET" }));
+    }
+
+    #[test]
+    fn validate_node_config_reports_first_violation() {
+        let registry = NodeTypeRegistry::builder()
+            .register(metadata("constant", NodeCategory::DataSource))
+            .build();
+
+        let ok = Node::new("c1", "constant").with_config(json!({ "value": "x" }));
+        assert!(registry.validate_node_config(&ok).is_ok());
+
+        let bad = Node::new("c2", "Constant").with_config(json!({ "value": 42 }));
+        let err = registry.validate_node_config(&bad).unwrap_err();
+        let ValidationError::InvalidNodeConfig {
+            node_id, reason, ..
+        } = err
+        else {
+            panic!("应为 InvalidNodeConfig");
+        };
+        assert_eq!(node_id, "c2");
+        assert!(reason.starts_with("/value"), "{reason}");
+
+        let unknown = Node::new("u", "acme.render");
+        assert_eq!(
+            registry.validate_node_config(&unknown),
+            Err(ValidationError::InvalidNodeConfig {
+                node_id: "u".into(),
+                node_type: "acme.render".into(),
+                reason: "unknown node type".into(),
+            })
+        );
+
+        let mut graph = NodeGraph::new();
+        for node in [ok, bad, unknown] {
+            graph.add_node(node).unwrap();
+        }
+        let errors = registry.validate_graph_configs(&graph);
+        assert_eq!(errors.len(), 2);
+        assert!(
+            matches!(&errors[0], ValidationError::InvalidNodeConfig { node_id, .. } if node_id == "c2")
+        );
+    }
+
+    #[test]
+    fn unregister_prunes_empty_category() {
+        let mut registry = NodeTypeRegistry::builder()
+            .register(metadata("trim", NodeCategory::Transform))
+            .register(metadata("upper", NodeCategory::Transform))
+            .register(metadata("limit", NodeCategory::Filter))
+            .build();
+
+        assert_eq!(registry.unregister("LIMIT").unwrap().type_id, "limit");
+        assert!(registry.by_category(NodeCategory::Filter).is_empty());
+        assert!(!registry.by_category.contains_key(&NodeCategory::Filter));
+        assert!(registry.unregister("limit").is_none());
+
+        registry.unregister("trim");
+        let ids: Vec<&str> = registry
+            .by_category(NodeCategory::Transform)
+            .iter()
+            .map(|m| m.type_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["upper"]);
+    }
+
+    #[test]
+    fn replace_swaps_definition_and_category() {
+        let mut registry = NodeTypeRegistry::builder()
+            .register(metadata("trim", NodeCategory::Transform))
+            .build();
+        let previous = registry
+            .replace(metadata("Trim", NodeCategory::Filter))
+            .unwrap();
+        assert_eq!(previous.unwrap().category, NodeCategory::Transform);
+        assert_eq!(registry.len(), 1);
+        assert!(registry.by_category(NodeCategory::Transform).is_empty());
+        assert_eq!(registry.by_category(NodeCategory::Filter).len(), 1);
+
+        assert!(registry
+            .replace(metadata("bad id", NodeCategory::Filter))
+            .is_err());
+        assert!(registry
+            .replace(metadata("new", NodeCategory::Output))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn try_register_surfaces_duplicates() {
+        let builder = NodeTypeRegistry::builder()
+            .try_register(metadata("trim", NodeCategory::Transform))
+            .unwrap();
+        let err = builder
+            .try_register(metadata("TRIM", NodeCategory::Filter))
+            .unwrap_err();
+        assert!(err.contains("trim"), "{err}");
+    }
+
+    #[test]
+    fn register_skips_duplicates_instead_of_panicking() {
+        let registry = NodeTypeRegistry::builder()
+            .register(metadata("trim", NodeCategory::Transform))
+            .register(metadata("Trim", NodeCategory::Filter))
+            .build();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(
+            registry.get("trim").unwrap().category,
+            NodeCategory::Transform
+        );
+    }
+
+    #[test]
+    fn builder_registers_types_by_category() {
+        let registry = NodeTypeRegistry::builder()
+            .register(metadata("http_request", NodeCategory::DataSource))
+            .register(metadata("constant", NodeCategory::DataSource))
+            .register(metadata("css_selector", NodeCategory::Selector))
+            .build();
+        assert_eq!(registry.len(), 3);
+        let sources: Vec<_> = registry
+            .by_category(NodeCategory::DataSource)
+            .iter()
+            .map(|m| m.type_id.as_str())
+            .collect();
+        assert_eq!(sources, vec!["http_request", "constant"]);
+        assert!(registry.by_category(NodeCategory::Output).is_empty());
+    }
+
+    #[test]
+    fn by_tag_and_non_deprecated_filters() {
+        let registry = NodeTypeRegistry::builder()
+            .register(metadata("css_selector", NodeCategory::Selector).with_tags(["HTML", "dom"]))
+            .register(
+                metadata("legacy_selector", NodeCategory::Selector)
+                    .with_tags(["html"])
+                    .deprecated(),
+            )
+            .register(metadata("constant", NodeCategory::DataSource))
+            .build();
+
+        let tagged: Vec<_> = registry
+            .by_tag("html")
+            .iter()
+            .map(|m| m.type_id.as_str())
+            .collect();
+        assert_eq!(tagged, vec!["css_selector", "legacy_selector"]);
+        assert!(registry.by_tag("xpath").is_empty());
+
+        let active: Vec<_> = registry
+            .non_deprecated()
+            .iter()
+            .map(|m| m.type_id.as_str())
+            .collect();
+        assert_eq!(active, vec!["constant", "css_selector"]);
+        assert!(registry.get("legacy_selector").unwrap().deprecated);
+    }
+
+    #[test]
+    fn search_matches_name_and_tags() {
+        let mut registry = NodeTypeRegistry::new();
+        registry
+            .register(
+                NodeTypeMetadata::new("css_selector", "CSS 选择器", NodeCategory::Selector)
+                    .with_tags(["html", "dom"]),
+            )
+            .unwrap();
+        registry
+            .register(metadata("constant", NodeCategory::DataSource))
+            .unwrap();
+        assert_eq!(registry.search("DOM").len(), 1);
+        assert_eq!(registry.search("css").len(), 1);
+        assert!(registry.search("xpath").is_empty());
+    }
+
+    #[test]
+    fn search_ranks_exact_prefix_substring_then_tags() {
+        let mut registry = NodeTypeRegistry::new();
+        for meta in [
+            NodeTypeMetadata::new("json_parse", "解析 JSON", NodeCategory::Transform),
+            NodeTypeMetadata::new("to_json", "JSON 序列化", NodeCategory::Transform),
+            NodeTypeMetadata::new("json", "JSON", NodeCategory::Transform),
+            NodeTypeMetadata::new("api_call", "接口请求", NodeCategory::DataSource)
+                .with_tags(["JSON"]),
+            NodeTypeMetadata::new("b_json_path", "JSON 路径", NodeCategory::Selector),
+        ] {
+            registry.register(meta).unwrap();
+        }
+
+        let ranked: Vec<(&str, f32)> = registry
+            .search_ranked("json")
+            .into_iter()
+            .map(|(m, score)| (m.type_id.as_str(), score))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("json", 1.0),
+                ("b_json_path", 0.8),
+                ("to_json", 0.8),
+                ("json_parse", 0.6),
+                ("api_call", 0.3),
+            ]
+        );
+        let ids: Vec<&str> = registry
+            .search("JSON")
+            .iter()
+            .map(|m| m.type_id.as_str())
+            .collect();
+        assert_eq!(
+            ids,
+            vec!["json", "b_json_path", "to_json", "json_parse", "api_call"]
+        );
+    }
+
+    #[test]
+    fn register_rejects_whitespace_and_empty_ids() {
+        let mut registry = NodeTypeRegistry::new();
+        assert!(registry
+            .register(metadata("http request", NodeCategory::DataSource))
+            .is_err());
+        assert!(registry
+            .register(metadata("", NodeCategory::DataSource))
+            .is_err());
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn case_variants_are_duplicates() {
+        let mut registry = NodeTypeRegistry::new();
+        registry
+            .register(metadata("HttpRequest", NodeCategory::DataSource))
+            .unwrap();
+        assert!(registry
+            .register(metadata("httprequest", NodeCategory::DataSource))
+            .is_err());
+        assert_eq!(registry.get("HTTPREQUEST").unwrap().type_id, "httprequest");
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn create_node_copies_port_definitions() {
+        let meta = NodeTypeMetadata::new("css_selector", "CSS", NodeCategory::Selector)
+            .with_input(PortDef::new("html", "HTML", DataType::String))
+            .with_output(PortDef::new("result", "结果", DataType::Array).optional());
+        let node = meta.create_node("n1");
+        assert_eq!(node.node_type, "css_selector");
+        assert!(node.inputs[0].required);
+        assert!(!node.outputs[0].required);
+    }
+}
@@ -0,0 +1,217 @@
+//! 内置节点类型
+//!
+//! 应用默认提供的一组入门节点，端口与配置和内置流程模板中的节点保持一致。
+
+use serde_json::json;
+
+use super::{NodeCategory, NodeTypeMetadata, NodeTypeRegistry, PortDef};
+use crate::domain::types::DataType;
+
+impl NodeTypeRegistry {
+    /// 注册了全部内置节点类型的注册表，作为编辑器节点面板的默认内容
+    pub fn with_builtins() -> Self {
+        builtin_types()
+            .into_iter()
+            .fold(Self::builder(), |builder, metadata| {
+                builder.register(metadata)
+            })
+            .build()
+    }
+}
+
+fn builtin_types() -> Vec<NodeTypeMetadata> {
+    vec![
+        http_request(),
+        constant(),
+        css_selector(),
+        regex_extract(),
+        condition(),
+        javascript(),
+        set_variable(),
+    ]
+}
+
+fn http_request() -> NodeTypeMetadata {
+    NodeTypeMetadata::new("http_request", "HTTP 请求", NodeCategory::DataSource)
+        .with_description("请求指定地址并输出响应内容与状态码")
+        .with_input(
+            PortDef::new("url", "地址", DataType::String)
+                .optional()
+                .with_description("连接时覆盖配置中的 url"),
+        )
+        .with_output(PortDef::new("body", "响应内容", DataType::String))
+        .with_output(PortDef::new("status", "状态码", DataType::Number))
+        .with_config_schema(json!({
+            "type": "object",
+            "properties": {
+                "url": { "type": "string" },
+                "method": {
+                    "type": "string",
+                    "enum": ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD"],
+                    "default": "GET"
+                },
+                "headers": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" }
+                },
+                "body": { "type": "string" },
+                "timeout_ms": { "type": "integer", "minimum": 0 }
+            }
+        }))
+        .with_tags(["http", "network", "fetch"])
+}
+
+fn constant() -> NodeTypeMetadata {
+    NodeTypeMetadata::new("constant", "常量", NodeCategory::DataSource)
+        .with_description("输出配置中的固定值")
+        .with_output(PortDef::new("value", "值", DataType::Any))
+        .with_config_schema(json!({
+            "type": "object",
+            "properties": {
+                "value": { "default": "" }
+            },
+            "required": ["value"]
+        }))
+        .with_tags(["value", "literal"])
+}
+
+fn css_selector() -> NodeTypeMetadata {
+    NodeTypeMetadata::new("css_selector", "CSS 选择器", NodeCategory::Selector)
+        .with_description("用 CSS 选择器从 HTML 中提取元素文本或属性")
+        .with_input(PortDef::new("html", "HTML", DataType::String))
+        .with_output(PortDef::new("result", "结果", DataType::Array))
+        .with_config_schema(json!({
+            "type": "object",
+            "properties": {
+                "selector": { "type": "string", "minLength": 1 },
+                "attribute": {
+                    "type": "string",
+                    "description": "要读取的属性，留空时取元素文本"
+                },
+                "first_only": { "type": "boolean", "default": false }
+            },
+            "required": ["selector"]
+        }))
+        .with_tags(["html", "css", "extract"])
+}
+
+fn regex_extract() -> NodeTypeMetadata {
+    NodeTypeMetadata::new("regex_extract", "正则提取", NodeCategory::Filter)
+        .with_description("用正则表达式匹配文本，输出指定分组的所有匹配")
+        .with_input(PortDef::new("text", "文本", DataType::String))
+        .with_output(PortDef::new("matches", "匹配结果", DataType::Array))
+        .with_config_schema(json!({
+            "type": "object",
+            "properties": {
+                "pattern": { "type": "string", "minLength": 1 },
+                "group": { "type": "integer", "minimum": 0, "default": 0 }
+            },
+            "required": ["pattern"]
+        }))
+        .with_tags(["regex", "text", "extract"])
+}
+
+fn condition() -> NodeTypeMetadata {
+    NodeTypeMetadata::new("condition", "条件分支", NodeCategory::Control)
+        .with_description("按条件把输入值送往“是”或“否”分支")
+        .with_input(PortDef::new("value", "值", DataType::Any))
+        .with_output(PortDef::new("true", "是", DataType::Any))
+        .with_output(PortDef::new("false", "否", DataType::Any))
+        .with_config_schema(json!({
+            "type": "object",
+            "properties": {
+                "operator": {
+                    "type": "string",
+                    "enum": [
+                        "equals", "not_equals", "contains", "matches",
+                        "greater_than", "less_than", "exists", "empty"
+                    ],
+                    "default": "exists"
+                },
+                "operand": {}
+            },
+            "required": ["operator"]
+        }))
+        .with_tags(["if", "branch"])
+}
+
+fn javascript() -> NodeTypeMetadata {
+    NodeTypeMetadata::new("javascript", "JavaScript 脚本", NodeCategory::Script)
+        .with_description("以输入值为 input 执行脚本，输出脚本的返回值")
+        .with_input(PortDef::new("input", "输入", DataType::Any).optional())
+        .with_output(PortDef::new("output", "输出", DataType::Any))
+        .with_config_schema(json!({
+            "type": "object",
+            "properties": {
+                "code": { "type": "string", "default": "return input;" },
+                "timeout_ms": { "type": "integer", "minimum": 0 }
+            },
+            "required": ["code"]
+        }))
+        .with_tags(["script", "js", "code"])
+}
+
+fn set_variable() -> NodeTypeMetadata {
+    NodeTypeMetadata::new("set_variable", "设置变量", NodeCategory::Output)
+        .with_description("把输入值写入流程变量，作为流程的输出")
+        .with_input(PortDef::new("value", "值", DataType::Any))
+        .with_config_schema(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1 }
+            },
+            "required": ["name"]
+        }))
+        .with_tags(["variable", "output"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{FlowTemplate, MediaType};
+
+    #[test]
+    fn builtins_register_expected_types_with_valid_schemas() {
+        let registry = NodeTypeRegistry::with_builtins();
+        for type_id in [
+            "http_request",
+            "css_selector",
+            "regex_extract",
+            "constant",
+            "set_variable",
+            "javascript",
+            "condition",
+        ] {
+            let metadata = registry.get(type_id).expect(type_id);
+            assert_eq!(metadata.config_schema["type"], "object", "{type_id}");
+            assert!(
+                jsonschema::validator_for(&metadata.config_schema).is_ok(),
+                "{type_id}"
+            );
+            // 选择器、正则与变量名没有合理的默认值，新建节点需要用户填写
+            let needs_input = matches!(type_id, "css_selector" | "regex_extract" | "set_variable");
+            let node = metadata.create_node("n1");
+            assert_eq!(
+                registry.validate_node_config(&node).is_ok(),
+                !needs_input,
+                "{type_id}"
+            );
+        }
+        assert_eq!(registry.len(), builtin_types().len());
+    }
+
+    #[test]
+    fn builtin_templates_use_valid_builtin_configs() {
+        let registry = NodeTypeRegistry::with_builtins();
+        for media in MediaType::all() {
+            for template in FlowTemplate::builtin_templates(media.clone()) {
+                assert_eq!(
+                    registry.validate_graph_configs(&template.graph),
+                    Vec::new(),
+                    "{}",
+                    template.id
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,705 @@
+//! 爬虫规则
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use specta::Type;
+
+use super::config::GlobalConfig;
+use super::error::{DomainError, Result};
+use super::flow::{FlowType, Flows};
+use super::media::MediaType;
+
+/// 发起 HTTP 请求的节点类型，其 `url` 配置用于推断域名
+const HTTP_REQUEST_NODE: &str = "http_request";
+
+/// 当前的规则格式版本
+pub const CURRENT_SCHEMA_VERSION: u32 = UPGRADES.len() as u32 + 1;
+
+/// 规则格式的升级步骤，第 `i` 项把版本 `i + 1` 的规则对象原地升级到 `i + 2`
+///
+/// 字段改名、结构调整等格式变化统一在这里追加一项，当前版本号随之递增。
+const UPGRADES: &[fn(&mut Map<String, Value>)] = &[];
+
+fn default_schema_version() -> u32 {
+    1
+}
+
+/// 规则元数据
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct Meta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 未识别的自定义字段
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Meta {
+    /// 用 `other` 覆盖本元数据，用于导入时套用模板元数据
+    ///
+    /// `other` 中为 `Some` 的字段覆盖本方字段，为 `None` 的不做改动；`tags` 取并集，
+    /// 保持本方原有顺序并把新标签依次追加在后；`extra` 按键合并，冲突时取 `other` 的值。
+    pub fn merge(&mut self, other: &Meta) {
+        let overlay = |field: &mut Option<String>, value: &Option<String>| {
+            if value.is_some() {
+                field.clone_from(value);
+            }
+        };
+        overlay(&mut self.author, &other.author);
+        overlay(&mut self.version, &other.version);
+        overlay(&mut self.description, &other.description);
+        overlay(&mut self.homepage, &other.homepage);
+        overlay(&mut self.icon, &other.icon);
+        for tag in &other.tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+        self.extra
+            .extend(other.extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+}
+
+/// 单个流程在两个规则版本之间的变化计数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct FlowDiffSummary {
+    pub flow_type: FlowType,
+    pub nodes_added: usize,
+    pub nodes_removed: usize,
+    pub nodes_modified: usize,
+    pub connections_added: usize,
+    pub connections_removed: usize,
+}
+
+/// 两个规则版本之间的概要变更，供评审时查看
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct RuleDiffSummary {
+    pub added_flows: Vec<FlowType>,
+    pub removed_flows: Vec<FlowType>,
+    /// 两边都存在且图结构有变化的流程
+    pub changed_flows: Vec<FlowDiffSummary>,
+}
+
+impl RuleDiffSummary {
+    pub fn is_empty(&self) -> bool {
+        self.added_flows.is_empty()
+            && self.removed_flows.is_empty()
+            && self.changed_flows.is_empty()
+    }
+}
+
+/// 一条完整的爬虫规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct CrawlerRule {
+    /// 持久化后由仓库分配
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    /// 规则格式版本，早于引入版本号的规则视为 1
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    pub name: String,
+    pub media_type: MediaType,
+    #[serde(default)]
+    pub meta: Meta,
+    #[serde(default)]
+    pub config: GlobalConfig,
+    #[serde(default)]
+    pub flows: Flows,
+}
+
+impl CrawlerRule {
+    pub fn new(name: impl Into<String>, media_type: MediaType) -> Self {
+        Self {
+            id: None,
+            schema_version: CURRENT_SCHEMA_VERSION,
+            name: name.into(),
+            media_type,
+            meta: Meta::default(),
+            config: GlobalConfig::default(),
+            flows: Flows::default(),
+        }
+    }
+
+    /// 将任意版本的规则 JSON 升级到当前格式后反序列化
+    ///
+    /// 缺少 `schema_version` 的数据视为版本 1，之后依次执行各版本的升级步骤
+    /// 直到当前版本；版本号为 0 或高于 [`CURRENT_SCHEMA_VERSION`] 时返回
+    /// [`DomainError::InvalidRule`]。
+    pub fn migrate(mut value: Value) -> Result<CrawlerRule> {
+        let object = value
+            .as_object_mut()
+            .ok_or_else(|| DomainError::InvalidRule("规则必须是 JSON 对象".to_string()))?;
+        let version = match object.get("schema_version") {
+            None => default_schema_version(),
+            Some(v) => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| DomainError::InvalidRule(format!("无效的 schema_version: {v}")))?,
+        };
+        if version == 0 || version > CURRENT_SCHEMA_VERSION {
+            return Err(DomainError::InvalidRule(format!(
+                "不支持的规则格式版本 {version}，当前版本为 {CURRENT_SCHEMA_VERSION}"
+            )));
+        }
+        for upgrade in &UPGRADES[version as usize - 1..] {
+            upgrade(object);
+        }
+        object.insert(
+            "schema_version".to_string(),
+            Value::from(CURRENT_SCHEMA_VERSION),
+        );
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn with_meta(mut self, meta: Meta) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    pub fn with_config(mut self, config: GlobalConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_flows(mut self, flows: Flows) -> Self {
+        self.flows = flows;
+        self
+    }
+
+    /// 校验规则：必需流程齐全，且每个流程自身合法
+    pub fn validate(&self) -> Result<()> {
+        self.flows.validate()?;
+        for (_, flow) in self.flows.defined_flows() {
+            flow.validate()?;
+        }
+        Ok(())
+    }
+
+    /// 按媒体类型检查规则是否完整，供保存前提示
+    ///
+    /// 目前对所有媒体类型都只做宽松检查：媒体类型要求的流程齐全，且内容流程至少有
+    /// 一个出口节点。所有问题以“；”连接后一并返回。
+    pub fn validate_media_consistency(&self) -> std::result::Result<(), String> {
+        let media = self.media_type.display_name();
+        let mut problems: Vec<String> = media_required_flows(&self.media_type)
+            .into_iter()
+            .filter(|flow_type| self.flows.get(*flow_type).is_none())
+            .map(|flow_type| format!("{media}规则缺少{}流程", flow_type.display_name()))
+            .collect();
+        if let Some(content) = self.flows.get(FlowType::Content) {
+            if content.exit_nodes().is_empty() {
+                problems.push(format!("{media}规则的内容流程没有出口节点"));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("；"))
+        }
+    }
+
+    /// 运行前需要预加载 Cookie 的域名
+    ///
+    /// 合并 [`GlobalConfig::cookie_domains`] 中声明的域名与从各流程
+    /// `http_request` 节点 `url` 配置推断出的主机名；统一转为小写、去掉前导 `.`，
+    /// 去重后按字典序返回。主机名中含占位符的 URL 会被忽略。
+    pub fn required_cookie_domains(&self) -> Vec<String> {
+        let mut domains: BTreeSet<String> = self
+            .config
+            .cookie_domains
+            .iter()
+            .map(|d| d.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|d| !d.is_empty())
+            .collect();
+
+        for (_, flow) in self.flows.defined_flows() {
+            for node in flow.graph.nodes.values() {
+                if node.node_type != HTTP_REQUEST_NODE {
+                    continue;
+                }
+                if let Some(host) = node
+                    .get_config::<String>("url")
+                    .as_deref()
+                    .and_then(url_host)
+                {
+                    domains.insert(host);
+                }
+            }
+        }
+        domains.into_iter().collect()
+    }
+
+    /// 对规则的 JSON 表示应用 RFC 6902 JSON Patch，编辑器自动保存时发送细粒度补丁
+    ///
+    /// 补丁格式错误或某个操作失败时返回 [`DomainError::InvalidRule`]，结果无法
+    /// 反序列化为规则时返回 [`DomainError::Serialization`]；出错时规则保持不变。
+    pub fn apply_json_patch(&mut self, patch: &Value) -> Result<()> {
+        let patch: json_patch::Patch = serde_json::from_value(patch.clone())
+            .map_err(|e| DomainError::InvalidRule(format!("无效的 JSON Patch: {e}")))?;
+        let mut document = serde_json::to_value(&*self)?;
+        json_patch::patch(&mut document, &patch)
+            .map_err(|e| DomainError::InvalidRule(format!("应用 JSON Patch 失败: {e}")))?;
+        *self = serde_json::from_value(document)?;
+        Ok(())
+    }
+
+    /// 规则内容的 SHA-256 指纹（小写十六进制），用于检测修改与导入去重
+    ///
+    /// 对键排序、连接排序后的规范 JSON 计算，因此与 `Meta.extra` 等映射的迭代
+    /// 顺序及连接的添加顺序无关；由仓库分配的 `id` 不参与计算。
+    pub fn fingerprint(&self) -> String {
+        let canonical = self.canonical_value().to_string();
+        format!("{:x}", Sha256::digest(canonical.as_bytes()))
+    }
+
+    /// 导出为用于分享的规范 JSON 文本
+    ///
+    /// 所有对象的键按字典序排列、缩进两个空格，同一内容总是得到相同的文本，
+    /// 文件差异干净且与 [`CrawlerRule::fingerprint`] 的计算口径一致；本地仓库
+    /// 分配的 `id` 不会导出。
+    pub fn to_pretty_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.canonical_value())?)
+    }
+
+    /// 从 JSON 文本导入规则，旧格式先经 [`CrawlerRule::migrate`] 升级
+    pub fn from_json_str(s: &str) -> Result<CrawlerRule> {
+        Self::migrate(serde_json::from_str(s)?)
+    }
+
+    /// 导出为便于手工编辑的 YAML 文本，内容与 [`CrawlerRule::to_pretty_json`] 相同
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(&self.canonical_value())?)
+    }
+
+    /// 从 YAML 文本导入规则
+    ///
+    /// 先解析为 JSON 值再经 [`CrawlerRule::migrate`] 升级与反序列化，与 JSON 导入走
+    /// 同一条路径，`Meta.extra` 的扁平字段也因此不受 YAML 反序列化器的影响。
+    pub fn from_yaml(s: &str) -> Result<CrawlerRule> {
+        Self::migrate(serde_yaml::from_str(s)?)
+    }
+
+    /// 去掉 `id`、对各流程的连接和所有对象的键排序后的 JSON 值
+    fn canonical_value(&self) -> Value {
+        let mut rule = self.clone();
+        rule.flows.sort_connections();
+        let mut value = serde_json::to_value(&rule).expect("规则总能序列化为 JSON");
+        if let Value::Object(map) = &mut value {
+            map.remove("id");
+        }
+        canonicalize(value)
+    }
+
+    /// 汇总从本规则到 `other` 的流程变更
+    ///
+    /// 按 [`FlowType::all`] 的顺序列出新增、删除的流程；两边都有的流程基于
+    /// [`NodeGraph::diff`](super::NodeGraph::diff) 统计节点与连接的变化数量，
+    /// 没有结构变化的流程不会出现在 `changed_flows` 中。
+    pub fn diff_summary(&self, other: &CrawlerRule) -> RuleDiffSummary {
+        let mut summary = RuleDiffSummary::default();
+        for &flow_type in FlowType::all() {
+            match (self.flows.get(flow_type), other.flows.get(flow_type)) {
+                (None, Some(_)) => summary.added_flows.push(flow_type),
+                (Some(_), None) => summary.removed_flows.push(flow_type),
+                (Some(before), Some(after)) => {
+                    let diff = before.graph.diff(&after.graph);
+                    if !diff.is_empty() {
+                        summary.changed_flows.push(FlowDiffSummary {
+                            flow_type,
+                            nodes_added: diff.added_nodes.len(),
+                            nodes_removed: diff.removed_nodes.len(),
+                            nodes_modified: diff.modified_nodes.len(),
+                            connections_added: diff.added_connections.len(),
+                            connections_removed: diff.removed_connections.len(),
+                        });
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+        summary
+    }
+}
+
+/// 媒体类型要求规则具备的流程，见 [`CrawlerRule::validate_media_consistency`]
+///
+/// 目前各类型都只要求 [`FlowType::is_required`] 的流程，媒体特有的要求在对应分支中追加。
+fn media_required_flows(media_type: &MediaType) -> Vec<FlowType> {
+    let required = FlowType::all()
+        .iter()
+        .copied()
+        .filter(FlowType::is_required);
+    match media_type {
+        MediaType::Video
+        | MediaType::Music
+        | MediaType::Novel
+        | MediaType::Comic
+        | MediaType::Image
+        | MediaType::General
+        | MediaType::Other(_) => required.collect(),
+    }
+}
+
+/// 按键名重建所有对象，使序列化结果与插入顺序无关
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonicalize(v)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonicalize).collect()),
+        other => other,
+    }
+}
+
+/// 提取 URL 中的主机名（小写，不含端口与认证信息）
+fn url_host(url: &str) -> Option<String> {
+    let (_, rest) = url.trim().split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority.rsplit('@').next()?;
+    let host = host_port.split(':').next()?;
+    if host.is_empty() || host.contains("{{") {
+        return None;
+    }
+    Some(host.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::graph::test_support::chain_graph;
+    use crate::domain::{DataType, Flow, FlowType, Node, NodeGraph, Port};
+    use serde_json::json;
+
+    fn request_flow(flow_type: FlowType, urls: &[&str]) -> Flow {
+        let mut graph = NodeGraph::new();
+        for (i, url) in urls.iter().enumerate() {
+            graph
+                .add_node(
+                    Node::new(format!("req{i}"), "http_request").with_config(json!({ "url": url })),
+                )
+                .unwrap();
+        }
+        Flow::new(flow_type, graph)
+    }
+
+    #[test]
+    fn fingerprint_ignores_extra_key_order_and_id() {
+        let mut a = Meta::default();
+        a.extra.insert("zeta".into(), json!(1));
+        a.extra.insert("alpha".into(), json!({ "y": 2, "x": 1 }));
+        let mut b = Meta::default();
+        b.extra.insert("alpha".into(), json!({ "x": 1, "y": 2 }));
+        b.extra.insert("zeta".into(), json!(1));
+
+        let rule_a = CrawlerRule::new("测试", MediaType::Video).with_meta(a);
+        let mut rule_b = CrawlerRule::new("测试", MediaType::Video).with_meta(b);
+        rule_b.id = Some(7);
+        let fingerprint = rule_a.fingerprint();
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(fingerprint, rule_b.fingerprint());
+
+        rule_b.name = "其他".into();
+        assert_ne!(fingerprint, rule_b.fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_ignores_connection_order() {
+        let graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let mut reversed = graph.clone();
+        reversed.connections.reverse();
+        let rule_a = CrawlerRule::new("测试", MediaType::Video)
+            .with_flows(Flows::new().with_search(Flow::new(FlowType::Search, graph)));
+        let rule_b = CrawlerRule::new("测试", MediaType::Video)
+            .with_flows(Flows::new().with_search(Flow::new(FlowType::Search, reversed)));
+        assert_ne!(rule_a, rule_b);
+        assert_eq!(rule_a.fingerprint(), rule_b.fingerprint());
+        assert_eq!(
+            rule_a.to_pretty_json().unwrap(),
+            rule_b.to_pretty_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn migrate_loads_legacy_rule_as_version_one() {
+        let rule =
+            CrawlerRule::migrate(json!({ "name": "旧规则", "media_type": "video" })).unwrap();
+        assert_eq!(rule.schema_version, 1);
+        assert_eq!(rule.name, "旧规则");
+
+        let legacy: CrawlerRule =
+            serde_json::from_value(json!({ "name": "旧规则", "media_type": "video" })).unwrap();
+        assert_eq!(legacy.schema_version, 1);
+
+        let err = CrawlerRule::migrate(json!({
+            "schema_version": CURRENT_SCHEMA_VERSION + 1,
+            "name": "未来规则",
+            "media_type": "video",
+        }))
+        .unwrap_err();
+        assert!(matches!(err, DomainError::InvalidRule(_)));
+        assert!(CrawlerRule::migrate(json!([])).is_err());
+    }
+
+    #[test]
+    fn pretty_json_is_canonical_and_round_trips() {
+        let mut meta = Meta {
+            author: Some("作者".into()),
+            ..Meta::default()
+        };
+        meta.extra.insert("zeta".into(), json!(1));
+        meta.extra.insert("alpha".into(), json!({ "y": 2, "x": 1 }));
+        let mut rule = CrawlerRule::new("测试", MediaType::Video).with_meta(meta);
+        rule.flows.insert(
+            FlowType::Search,
+            request_flow(FlowType::Search, &["https://a.com/s?q={{keyword}}"]),
+        );
+        rule.id = Some(3);
+
+        let text = rule.to_pretty_json().unwrap();
+        assert!(!text.contains("\n  \"id\""));
+        assert!(text.contains("\n  \"flows\""));
+        assert!(text.find("\"alpha\"").unwrap() < text.find("\"author\"").unwrap());
+        assert!(text.find("\"x\"").unwrap() < text.find("\"y\"").unwrap());
+
+        let imported = CrawlerRule::from_json_str(&text).unwrap();
+        assert_eq!(imported, CrawlerRule { id: None, ..rule });
+        assert_eq!(imported.to_pretty_json().unwrap(), text);
+    }
+
+    #[test]
+    fn from_json_str_imports_legacy_format() {
+        let rule =
+            CrawlerRule::from_json_str(r#"{ "name": "旧规则", "media_type": "video" }"#).unwrap();
+        assert_eq!(rule.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(rule.name, "旧规则");
+        assert!(matches!(
+            CrawlerRule::from_json_str("{"),
+            Err(DomainError::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn yaml_round_trips_meta_extra() {
+        let mut meta = Meta {
+            tags: vec!["动画".into()],
+            ..Meta::default()
+        };
+        meta.extra.insert("license".into(), json!("MIT"));
+        meta.extra.insert(
+            "mirror".into(),
+            json!({ "weight": 2, "urls": ["https://b.com"] }),
+        );
+        meta.extra.insert("rating".into(), json!(4.5));
+        let mut rule = CrawlerRule::new("测试", MediaType::Video).with_meta(meta);
+        rule.flows.insert(
+            FlowType::Search,
+            request_flow(FlowType::Search, &["https://a.com/s?q={{keyword}}"]),
+        );
+
+        let yaml = rule.to_yaml().unwrap();
+        assert!(yaml.contains("license: MIT"));
+        let imported = CrawlerRule::from_yaml(&yaml).unwrap();
+        assert_eq!(imported, rule);
+        assert_eq!(imported.meta.extra["mirror"]["weight"], json!(2));
+
+        let hand_written =
+            "name: 手写规则\nmedia_type: video\nmeta:\n  author: 某人\n  license: MIT\n";
+        let rule = CrawlerRule::from_yaml(hand_written).unwrap();
+        assert_eq!(rule.meta.author.as_deref(), Some("某人"));
+        assert_eq!(rule.meta.extra["license"], json!("MIT"));
+        assert!(matches!(
+            CrawlerRule::from_yaml("name: [\n"),
+            Err(DomainError::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn meta_merge_overrides_unions_tags_and_extra() {
+        let mut meta = Meta {
+            author: Some("原作者".into()),
+            version: Some("1.0".into()),
+            tags: vec!["动画".into(), "高清".into()],
+            ..Meta::default()
+        };
+        meta.extra.insert("license".into(), json!("MIT"));
+        meta.extra.insert("region".into(), json!("cn"));
+        let mut template = Meta {
+            version: Some("2.0".into()),
+            icon: Some("icon.png".into()),
+            tags: vec!["高清".into(), "合集".into()],
+            ..Meta::default()
+        };
+        template.extra.insert("license".into(), json!("GPL"));
+
+        meta.merge(&template);
+        assert_eq!(meta.author.as_deref(), Some("原作者"));
+        assert_eq!(meta.version.as_deref(), Some("2.0"));
+        assert_eq!(meta.icon.as_deref(), Some("icon.png"));
+        assert_eq!(meta.description, None);
+        assert_eq!(meta.tags, vec!["动画", "高清", "合集"]);
+        assert_eq!(meta.extra["license"], json!("GPL"));
+        assert_eq!(meta.extra["region"], json!("cn"));
+
+        let before = meta.clone();
+        meta.merge(&Meta::default());
+        assert_eq!(meta, before);
+    }
+
+    #[test]
+    fn media_consistency_requires_content_flow_with_exit() {
+        let mut rule = CrawlerRule::new("视频", MediaType::Video);
+        for flow_type in [FlowType::Search, FlowType::Detail] {
+            rule.flows
+                .insert(flow_type, request_flow(flow_type, &["https://a.com"]));
+        }
+        let err = rule.validate_media_consistency().unwrap_err();
+        assert_eq!(err, "视频规则缺少内容流程");
+
+        // 连接到自身的内容节点既有输入也有输出，没有出口
+        let mut graph = NodeGraph::new();
+        graph
+            .add_node(
+                Node::new("loop", "test")
+                    .with_input(Port::new("in", "in", DataType::Any))
+                    .with_output(Port::new("out", "out", DataType::Any)),
+            )
+            .unwrap();
+        rule.flows
+            .insert(FlowType::Content, Flow::new(FlowType::Content, graph));
+        let err = rule.validate_media_consistency().unwrap_err();
+        assert!(err.contains("出口节点"), "{err}");
+
+        rule.flows.insert(
+            FlowType::Content,
+            request_flow(FlowType::Content, &["https://a.com"]),
+        );
+        assert_eq!(rule.validate_media_consistency(), Ok(()));
+    }
+
+    #[test]
+    fn url_host_extracts_hostname() {
+        assert_eq!(
+            url_host("https://User:pw@Api.Example.com:8080/a?b").as_deref(),
+            Some("api.example.com")
+        );
+        assert_eq!(url_host("{{base_url}}/search"), None);
+        assert_eq!(url_host("https://{{host}}/x"), None);
+    }
+
+    #[test]
+    fn required_cookie_domains_merges_declared_and_inferred() {
+        let mut rule = CrawlerRule::new("测试", MediaType::Video).with_flows(
+            Flows::new()
+                .with_search(request_flow(
+                    FlowType::Search,
+                    &["https://api.other.com/search?q=1"],
+                ))
+                .with_detail(request_flow(
+                    FlowType::Detail,
+                    &["https://Example.com/item/1"],
+                )),
+        );
+        rule.config.cookie_domains = vec![".example.com".into()];
+
+        assert_eq!(
+            rule.required_cookie_domains(),
+            vec!["api.other.com".to_string(), "example.com".to_string()]
+        );
+    }
+
+    #[test]
+    fn diff_summary_counts_changes_per_flow() {
+        let before = CrawlerRule::new("测试", MediaType::Video).with_flows(
+            Flows::new()
+                .with_search(request_flow(FlowType::Search, &["https://a.com/s"]))
+                .with_login(request_flow(FlowType::Login, &["https://a.com/login"])),
+        );
+        let mut after = before.clone();
+        after.flows.remove(FlowType::Login);
+        after.flows.insert(
+            FlowType::Detail,
+            request_flow(FlowType::Detail, &["https://a.com/d"]),
+        );
+        after
+            .flows
+            .get_mut(FlowType::Search)
+            .unwrap()
+            .graph
+            .add_node(Node::new("parse", "css_selector"))
+            .unwrap();
+
+        let summary = before.diff_summary(&after);
+        assert_eq!(summary.added_flows, vec![FlowType::Detail]);
+        assert_eq!(summary.removed_flows, vec![FlowType::Login]);
+        assert_eq!(
+            summary.changed_flows,
+            vec![FlowDiffSummary {
+                flow_type: FlowType::Search,
+                nodes_added: 1,
+                nodes_removed: 0,
+                nodes_modified: 0,
+                connections_added: 0,
+                connections_removed: 0,
+            }]
+        );
+        assert!(before.diff_summary(&before).is_empty());
+    }
+
+    #[test]
+    fn apply_json_patch_replaces_name() {
+        let mut rule = CrawlerRule::new("旧名称", MediaType::Video);
+        rule.apply_json_patch(&json!([
+            { "op": "replace", "path": "/name", "value": "新名称" },
+            { "op": "add", "path": "/meta/tags", "value": ["hd"] },
+        ]))
+        .unwrap();
+        assert_eq!(rule.name, "新名称");
+        assert_eq!(rule.meta.tags, vec!["hd"]);
+    }
+
+    #[test]
+    fn apply_json_patch_errors_leave_rule_untouched() {
+        let mut rule = CrawlerRule::new("测试", MediaType::Video);
+        let original = rule.clone();
+
+        let err = rule
+            .apply_json_patch(&json!([{ "op": "remove", "path": "/missing" }]))
+            .unwrap_err();
+        assert!(matches!(err, DomainError::InvalidRule(_)));
+        let err = rule
+            .apply_json_patch(&json!([{ "op": "replace", "path": "/media_type", "value": 42 }]))
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Serialization(_)));
+        assert!(rule.apply_json_patch(&json!({ "op": "add" })).is_err());
+        assert_eq!(rule, original);
+    }
+
+    #[test]
+    fn meta_keeps_unknown_fields_in_extra() {
+        let meta: Meta =
+            serde_json::from_value(json!({ "author": "a", "license": "MIT" })).unwrap();
+        assert_eq!(meta.author.as_deref(), Some("a"));
+        assert_eq!(meta.extra["license"], json!("MIT"));
+    }
+}
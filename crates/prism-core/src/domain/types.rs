@@ -0,0 +1,265 @@
+//! 端口数据类型
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 端口上流动的数据类型
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DataType {
+    /// 任意类型，可与所有类型互连
+    Any,
+    String,
+    /// 任意数值，整数与浮点数都可以流入
+    Number,
+    Integer,
+    Float,
+    Boolean,
+    /// 日期时间，运行时以 RFC 3339 字符串表示
+    Date,
+    Array,
+    Object,
+}
+
+impl DataType {
+    /// 所有数据类型
+    pub fn all() -> &'static [DataType] {
+        &[
+            Self::Any,
+            Self::String,
+            Self::Number,
+            Self::Integer,
+            Self::Float,
+            Self::Boolean,
+            Self::Date,
+            Self::Array,
+            Self::Object,
+        ]
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Any => "any",
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::Array => "array",
+            Self::Object => "object",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 判断 `from` 类型的输出能否连接到 `to` 类型的输入
+///
+/// 相同类型总是兼容，任意一端为 [`DataType::Any`] 时也兼容。其余允许的转换如下
+/// （行为输出类型，列出可流入的输入类型）：
+///
+/// | `from` | 可流入的 `to` |
+/// | --- | --- |
+/// | `Integer` | `Float`、`Number`、`String` |
+/// | `Float` | `Number`、`String` |
+/// | `Number` | `String` |
+/// | `Date` | `String` |
+/// | `Array` | `Object`（数组被视为通用 JSON 容器） |
+///
+/// 其他组合（如 `Number` 流入 `Integer`、`String` 流入 `Date`）均不兼容。
+pub fn is_type_compatible(from: DataType, to: DataType) -> bool {
+    use DataType::*;
+    match (from, to) {
+        (Any, _) | (_, Any) => true,
+        (Integer, Float | Number | String) => true,
+        (Float, Number | String) => true,
+        (Number | Date, String) => true,
+        (Array, Object) => true,
+        (a, b) => a == b,
+    }
+}
+
+/// 可配置的类型兼容规则：允许的有向类型转换集合
+///
+/// 相同类型与涉及 [`DataType::Any`] 的连接始终兼容，无需列入集合。
+/// [`TypeCompatibility::default`] 与 [`is_type_compatible`] 的规则一致；
+/// 需要更严格的图（如某些媒体类型不允许数组流入对象）可以在此基础上移除转换。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct TypeCompatibility {
+    allowed: BTreeSet<(DataType, DataType)>,
+}
+
+impl Default for TypeCompatibility {
+    fn default() -> Self {
+        let mut allowed = BTreeSet::new();
+        for &from in DataType::all() {
+            for &to in DataType::all() {
+                if from != to
+                    && from != DataType::Any
+                    && to != DataType::Any
+                    && is_type_compatible(from, to)
+                {
+                    allowed.insert((from, to));
+                }
+            }
+        }
+        Self { allowed }
+    }
+}
+
+impl TypeCompatibility {
+    /// 只允许相同类型与 `Any` 的连接
+    pub fn identity_only() -> Self {
+        Self {
+            allowed: BTreeSet::new(),
+        }
+    }
+
+    /// 允许 `from` 流入 `to`
+    pub fn allow(mut self, from: DataType, to: DataType) -> Self {
+        self.allowed.insert((from, to));
+        self
+    }
+
+    /// 禁止 `from` 流入 `to`（相同类型与 `Any` 的连接无法禁止）
+    pub fn forbid(mut self, from: DataType, to: DataType) -> Self {
+        self.allowed.remove(&(from, to));
+        self
+    }
+
+    /// 在该规则下 `from` 类型的输出能否连接到 `to` 类型的输入
+    pub fn is_compatible(&self, from: DataType, to: DataType) -> bool {
+        from == to
+            || from == DataType::Any
+            || to == DataType::Any
+            || self.allowed.contains(&(from, to))
+    }
+}
+
+/// 校验连接时的类型转换策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeCoercion {
+    /// 只允许 [`is_type_compatible`] 规定的连接（默认）
+    #[default]
+    Strict,
+    /// 另外允许 `Boolean` 流入 `String`，由执行时转换为文本
+    Lenient,
+}
+
+impl TypeCoercion {
+    /// 该策略对应的兼容规则
+    pub fn compatibility(&self) -> TypeCompatibility {
+        match self {
+            Self::Strict => TypeCompatibility::default(),
+            Self::Lenient => {
+                TypeCompatibility::default().allow(DataType::Boolean, DataType::String)
+            }
+        }
+    }
+
+    /// 在该策略下 `from` 类型的输出能否连接到 `to` 类型的输入
+    pub fn is_compatible(&self, from: DataType, to: DataType) -> bool {
+        self.compatibility().is_compatible(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_is_compatible_both_ways() {
+        assert!(is_type_compatible(DataType::Any, DataType::Number));
+        assert!(is_type_compatible(DataType::String, DataType::Any));
+    }
+
+    #[test]
+    fn identical_types_are_compatible() {
+        assert!(is_type_compatible(DataType::String, DataType::String));
+        assert!(!is_type_compatible(DataType::String, DataType::Number));
+    }
+
+    #[test]
+    fn compatibility_matrix_is_exhaustive() {
+        use DataType::*;
+        let allowed = [
+            (Integer, Float),
+            (Integer, Number),
+            (Integer, String),
+            (Float, Number),
+            (Float, String),
+            (Number, String),
+            (Date, String),
+            (Array, Object),
+        ];
+        for &from in DataType::all() {
+            for &to in DataType::all() {
+                let expected =
+                    from == to || from == Any || to == Any || allowed.contains(&(from, to));
+                assert_eq!(is_type_compatible(from, to), expected, "{from} -> {to}");
+            }
+        }
+    }
+
+    #[test]
+    fn new_types_serialize_lowercase() {
+        assert_eq!(DataType::Integer.to_string(), "integer");
+        assert_eq!(
+            serde_json::to_value(DataType::Date).unwrap(),
+            serde_json::json!("date")
+        );
+        assert_eq!(
+            serde_json::from_value::<DataType>(serde_json::json!("float")).unwrap(),
+            DataType::Float
+        );
+    }
+
+    #[test]
+    fn lenient_coercion_allows_boolean_into_string() {
+        assert!(TypeCoercion::Strict.is_compatible(DataType::Number, DataType::String));
+        assert!(!TypeCoercion::Strict.is_compatible(DataType::Boolean, DataType::String));
+        assert!(TypeCoercion::Lenient.is_compatible(DataType::Boolean, DataType::String));
+        assert!(!TypeCoercion::Lenient.is_compatible(DataType::String, DataType::Number));
+        assert!(!TypeCoercion::Lenient.is_compatible(DataType::Object, DataType::String));
+    }
+
+    #[test]
+    fn default_compatibility_matches_builtin_rules() {
+        let compatibility = TypeCompatibility::default();
+        for &from in DataType::all() {
+            for &to in DataType::all() {
+                assert_eq!(
+                    compatibility.is_compatible(from, to),
+                    is_type_compatible(from, to),
+                    "{from} -> {to}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn custom_compatibility_can_forbid_and_allow() {
+        let strict = TypeCompatibility::default().forbid(DataType::Array, DataType::Object);
+        assert!(!strict.is_compatible(DataType::Array, DataType::Object));
+        assert!(strict.is_compatible(DataType::Array, DataType::Array));
+        assert!(strict.is_compatible(DataType::Array, DataType::Any));
+
+        let none = TypeCompatibility::identity_only().allow(DataType::String, DataType::Date);
+        assert!(none.is_compatible(DataType::String, DataType::Date));
+        assert!(!none.is_compatible(DataType::Integer, DataType::Number));
+    }
+
+    #[test]
+    fn array_flows_into_object_but_not_back() {
+        assert!(is_type_compatible(DataType::Array, DataType::Object));
+        assert!(!is_type_compatible(DataType::Object, DataType::Array));
+    }
+}
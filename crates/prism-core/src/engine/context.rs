@@ -0,0 +1,60 @@
+//! 流程执行上下文
+
+use crate::domain::FlowType;
+
+/// 单个流程一次运行的上下文
+///
+/// 缓存键默认以流程类型为命名空间（如 `search:`），使不同流程中碰巧相同的
+/// 键（例如同一个 URL）互不串用；需要跨流程共享缓存时可通过
+/// [`ExecutionContext::with_cache_namespace`] 指定相同的命名空间。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionContext {
+    pub flow_type: FlowType,
+    cache_namespace: String,
+}
+
+impl ExecutionContext {
+    pub fn new(flow_type: FlowType) -> Self {
+        Self {
+            flow_type,
+            cache_namespace: flow_type.as_str().to_string(),
+        }
+    }
+
+    /// 覆盖缓存命名空间，用于在多个流程之间共享缓存
+    pub fn with_cache_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.cache_namespace = namespace.into();
+        self
+    }
+
+    pub fn cache_namespace(&self) -> &str {
+        &self.cache_namespace
+    }
+
+    /// 带命名空间前缀的缓存键，请求去重与响应缓存都应使用它而不是原始键
+    pub fn cache_key(&self, key: &str) -> String {
+        format!("{}:{key}", self.cache_namespace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com/list?page=1";
+
+    #[test]
+    fn cache_keys_are_namespaced_per_flow() {
+        let discovery = ExecutionContext::new(FlowType::Discovery);
+        let search = ExecutionContext::new(FlowType::Search);
+        assert_ne!(discovery.cache_key(URL), search.cache_key(URL));
+        assert_eq!(search.cache_key(URL), format!("search:{URL}"));
+    }
+
+    #[test]
+    fn shared_namespace_allows_cross_flow_reuse() {
+        let discovery = ExecutionContext::new(FlowType::Discovery).with_cache_namespace("shared");
+        let search = ExecutionContext::new(FlowType::Search).with_cache_namespace("shared");
+        assert_eq!(discovery.cache_key(URL), search.cache_key(URL));
+    }
+}
@@ -0,0 +1,12 @@
+//! 执行引擎
+//!
+//! 引擎只负责调度：按依赖顺序执行节点、在节点之间传递数据并处理失败，
+//! 每个节点的具体行为由 [`NodeExecutor`] 实现提供。
+
+mod context;
+mod parallel;
+mod runner;
+
+pub use context::ExecutionContext;
+pub use parallel::ParallelGraphRunner;
+pub use runner::{GraphRunner, NodeExecutor, NodeOutputs, RunReport};
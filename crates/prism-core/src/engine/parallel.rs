@@ -0,0 +1,193 @@
+//! 按层并行的节点图运行器
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use super::runner::{collect_inputs, NodeExecutor, RunReport};
+use crate::domain::{DomainError, ErrorPolicy, NodeGraph, NodeTypeRegistry, Result};
+
+/// 按依赖层级并行执行节点图
+///
+/// 同一层的节点互不依赖，会被同时调度；层与层之间依次推进。注册表中声明了
+/// [`max_global_concurrency`](crate::domain::NodeTypeMetadata::max_global_concurrency)
+/// 的节点类型在执行前需取得该类型的信号量许可，许可由运行器持有，因此对同一
+/// 运行器上的所有运行（包括并发的多次运行）都生效。
+pub struct ParallelGraphRunner {
+    executor: Arc<dyn NodeExecutor>,
+    /// 规范化类型 ID → 该类型的并发许可
+    limits: HashMap<String, Arc<Semaphore>>,
+}
+
+impl ParallelGraphRunner {
+    pub fn new(executor: Arc<dyn NodeExecutor>) -> Self {
+        Self {
+            executor,
+            limits: HashMap::new(),
+        }
+    }
+
+    /// 从注册表读取各节点类型的全局并发上限
+    ///
+    /// 上限为 `0` 的类型视为不限，否则零许可的信号量会让该类型的节点永远等待。
+    pub fn with_registry(mut self, registry: &NodeTypeRegistry) -> Self {
+        self.limits = registry
+            .all()
+            .into_iter()
+            .filter_map(|meta| {
+                let limit = meta.max_global_concurrency.filter(|limit| *limit > 0)?;
+                Some((meta.type_id.clone(), Arc::new(Semaphore::new(limit))))
+            })
+            .collect();
+        self
+    }
+
+    /// 运行整张图，失败处理与 [`GraphRunner::run`](super::GraphRunner::run) 相同
+    pub async fn run(&self, graph: &NodeGraph) -> Result<RunReport> {
+        let mut report = RunReport::default();
+        let mut blocked: HashSet<String> = HashSet::new();
+
+        for layer in graph.execution_layers()? {
+            let mut tasks = JoinSet::new();
+            for node_id in layer {
+                if graph
+                    .get_dependencies(&node_id)
+                    .iter()
+                    .any(|dep| blocked.contains(dep))
+                {
+                    tracing::debug!(node_id = %node_id, "上游节点失败，跳过执行");
+                    blocked.insert(node_id.clone());
+                    report.unsatisfied.push(node_id);
+                    continue;
+                }
+
+                let node = graph.nodes[&node_id].clone();
+                tracing::debug!(node_id = %node_id, node_type = %node.node_type, config = %node.redacted_config(), "执行节点");
+                let inputs = collect_inputs(graph, &node_id, &report.outputs);
+                let executor = Arc::clone(&self.executor);
+                let permits = self
+                    .limits
+                    .get(&node.node_type.to_ascii_lowercase())
+                    .cloned();
+                tasks.spawn(async move {
+                    let _permit = match permits {
+                        Some(semaphore) => Some(
+                            semaphore
+                                .acquire_owned()
+                                .await
+                                .map_err(|e| DomainError::Execution(e.to_string()))?,
+                        ),
+                        None => None,
+                    };
+                    let result = executor.execute(&node, &inputs).await;
+                    Ok::<_, DomainError>((node, result))
+                });
+            }
+
+            while let Some(joined) = tasks.join_next().await {
+                let (node, result) = joined
+                    .map_err(|e| DomainError::Execution(format!("节点任务异常结束: {e}")))??;
+                match result {
+                    Ok(outputs) => {
+                        report.outputs.insert(node.id, outputs);
+                    }
+                    Err(err) if node.error_policy() == ErrorPolicy::Skip => {
+                        tracing::warn!(node_id = %node.id, error = %err, "节点执行失败，按 skip 策略继续");
+                        blocked.insert(node.id.clone());
+                        report.failed.push((node.id, err.to_string()));
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Node, NodeCategory, NodeTypeMetadata};
+    use crate::engine::NodeOutputs;
+    use async_trait::async_trait;
+    use serde_json::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// 记录同时执行的节点数峰值
+    #[derive(Default)]
+    struct TrackingExecutor {
+        running: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl NodeExecutor for TrackingExecutor {
+        async fn execute(&self, _: &Node, _: &HashMap<String, Value>) -> Result<NodeOutputs> {
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(NodeOutputs::new())
+        }
+    }
+
+    fn two_browser_nodes() -> NodeGraph {
+        let mut graph = NodeGraph::new();
+        graph.add_node(Node::new("r1", "browser_render")).unwrap();
+        graph.add_node(Node::new("r2", "Browser_Render")).unwrap();
+        graph
+    }
+
+    #[tokio::test]
+    async fn same_layer_nodes_run_concurrently() {
+        let executor = Arc::new(TrackingExecutor::default());
+        let report = ParallelGraphRunner::new(executor.clone())
+            .run(&two_browser_nodes())
+            .await
+            .unwrap();
+        assert!(report.is_complete());
+        assert_eq!(executor.peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn limited_type_never_runs_concurrently() {
+        let registry = NodeTypeRegistry::builder()
+            .register(
+                NodeTypeMetadata::new("browser_render", "浏览器渲染", NodeCategory::DataSource)
+                    .with_max_global_concurrency(1),
+            )
+            .build();
+        let executor = Arc::new(TrackingExecutor::default());
+        let report = ParallelGraphRunner::new(executor.clone())
+            .with_registry(&registry)
+            .run(&two_browser_nodes())
+            .await
+            .unwrap();
+        assert_eq!(report.outputs.len(), 2);
+        assert_eq!(executor.peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn zero_limit_means_unlimited() {
+        let registry = NodeTypeRegistry::builder()
+            .register(
+                NodeTypeMetadata::new("browser_render", "浏览器渲染", NodeCategory::DataSource)
+                    .with_max_global_concurrency(0),
+            )
+            .build();
+        let executor = Arc::new(TrackingExecutor::default());
+        let runner = ParallelGraphRunner::new(executor.clone()).with_registry(&registry);
+        let report = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            runner.run(&two_browser_nodes()),
+        )
+        .await
+        .expect("上限为 0 时不应挂起")
+        .unwrap();
+        assert!(report.is_complete());
+        assert_eq!(executor.peak.load(Ordering::SeqCst), 2);
+    }
+}
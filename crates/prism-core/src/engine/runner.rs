@@ -0,0 +1,192 @@
+//! 节点图运行器
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::domain::{ErrorPolicy, Node, NodeGraph, Result};
+
+/// 节点输出：输出端口 ID → 值
+pub type NodeOutputs = HashMap<String, Value>;
+
+/// 单个节点的执行逻辑
+#[async_trait]
+pub trait NodeExecutor: Send + Sync {
+    /// 执行节点；`inputs` 以输入端口 ID 为键，值来自上游节点对应的输出端口
+    async fn execute(&self, node: &Node, inputs: &HashMap<String, Value>) -> Result<NodeOutputs>;
+}
+
+/// 一次运行的结果
+#[derive(Debug, Clone, Default)]
+pub struct RunReport {
+    /// 成功执行的节点输出
+    pub outputs: HashMap<String, NodeOutputs>,
+    /// 按 `skip` 策略被容忍的失败：节点 ID 与错误信息
+    pub failed: Vec<(String, String)>,
+    /// 因上游失败而未执行的节点
+    pub unsatisfied: Vec<String>,
+}
+
+impl RunReport {
+    /// 是否所有节点都执行成功
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty() && self.unsatisfied.is_empty()
+    }
+}
+
+/// 按拓扑顺序依次执行节点图
+pub struct GraphRunner {
+    executor: Arc<dyn NodeExecutor>,
+}
+
+impl GraphRunner {
+    pub fn new(executor: Arc<dyn NodeExecutor>) -> Self {
+        Self { executor }
+    }
+
+    /// 运行整张图
+    ///
+    /// 执行顺序取 [`NodeGraph::topological_sort_stable`]，同一张图每次运行的节点顺序、
+    /// 日志与 [`RunReport`] 中的记录顺序都相同。
+    ///
+    /// 节点失败时根据其 [`ErrorPolicy`] 处理：`Abort` 直接返回错误；`Skip` 记录
+    /// 失败并继续，所有（直接或间接）依赖该节点的下游节点不再执行，记入
+    /// [`RunReport::unsatisfied`]，与之无关的分支照常完成。
+    pub async fn run(&self, graph: &NodeGraph) -> Result<RunReport> {
+        let order = graph.topological_sort_stable()?;
+        let mut report = RunReport::default();
+        let mut blocked: HashSet<String> = HashSet::new();
+
+        for node_id in order {
+            let node = &graph.nodes[&node_id];
+            if graph
+                .get_dependencies(&node_id)
+                .iter()
+                .any(|dep| blocked.contains(dep))
+            {
+                tracing::debug!(node_id = %node_id, "上游节点失败，跳过执行");
+                blocked.insert(node_id.clone());
+                report.unsatisfied.push(node_id);
+                continue;
+            }
+
+            let inputs = collect_inputs(graph, &node_id, &report.outputs);
+            tracing::debug!(node_id = %node_id, node_type = %node.node_type, config = %node.redacted_config(), "执行节点");
+            match self.executor.execute(node, &inputs).await {
+                Ok(outputs) => {
+                    report.outputs.insert(node_id, outputs);
+                }
+                Err(err) if node.error_policy() == ErrorPolicy::Skip => {
+                    tracing::warn!(node_id = %node_id, error = %err, "节点执行失败，按 skip 策略继续");
+                    blocked.insert(node_id.clone());
+                    report.failed.push((node_id, err.to_string()));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// 汇集节点各输入端口的上游输出
+pub(super) fn collect_inputs(
+    graph: &NodeGraph,
+    node_id: &str,
+    outputs: &HashMap<String, NodeOutputs>,
+) -> HashMap<String, Value> {
+    graph
+        .connections
+        .iter()
+        .filter(|c| c.to_node == node_id)
+        .filter_map(|c| {
+            let value = outputs.get(&c.from_node)?.get(&c.from_port)?;
+            Some((c.to_port.clone(), value.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::graph::test_support::chain_graph;
+    use crate::domain::DomainError;
+    use serde_json::json;
+
+    /// 把输入原样传到 `out`，配置中 `fail` 为真时失败
+    struct EchoExecutor;
+
+    #[async_trait]
+    impl NodeExecutor for EchoExecutor {
+        async fn execute(
+            &self,
+            node: &Node,
+            inputs: &HashMap<String, Value>,
+        ) -> Result<NodeOutputs> {
+            if node.get_config::<bool>("fail").unwrap_or(false) {
+                return Err(DomainError::Execution(format!("{} 失败", node.id)));
+            }
+            let value = inputs.get("in").cloned().unwrap_or(json!(node.id));
+            Ok(HashMap::from([("out".to_string(), value)]))
+        }
+    }
+
+    fn runner() -> GraphRunner {
+        GraphRunner::new(Arc::new(EchoExecutor))
+    }
+
+    #[tokio::test]
+    async fn passes_values_downstream() {
+        let graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let report = runner().run(&graph).await.unwrap();
+        assert!(report.is_complete());
+        assert_eq!(report.outputs["b"]["out"], json!("a"));
+    }
+
+    #[tokio::test]
+    async fn skip_policy_lets_independent_branch_complete() {
+        let mut graph = chain_graph(
+            &["a", "b", "c", "d", "e"],
+            &[("a", "b"), ("b", "c"), ("d", "e")],
+        );
+        let a = graph.get_node_mut("a").unwrap();
+        a.set_config("fail", json!(true));
+        a.set_config("_on_error", json!("skip"));
+
+        let report = runner().run(&graph).await.unwrap();
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "a");
+        let mut unsatisfied = report.unsatisfied.clone();
+        unsatisfied.sort();
+        assert_eq!(unsatisfied, vec!["b", "c"]);
+        assert_eq!(report.outputs["e"]["out"], json!("d"));
+        assert!(!report.is_complete());
+    }
+
+    #[tokio::test]
+    async fn runs_independent_nodes_in_id_order() {
+        let mut graph = chain_graph(&["c", "a", "b"], &[]);
+        for id in ["a", "b", "c"] {
+            let node = graph.get_node_mut(id).unwrap();
+            node.set_config("fail", json!(true));
+            node.set_config("_on_error", json!("skip"));
+        }
+        let report = runner().run(&graph).await.unwrap();
+        let failed: Vec<_> = report.failed.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(failed, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn abort_policy_fails_the_run() {
+        let mut graph = chain_graph(&["a", "b", "d"], &[("a", "b")]);
+        graph
+            .get_node_mut("a")
+            .unwrap()
+            .set_config("fail", json!(true));
+        assert!(matches!(
+            runner().run(&graph).await,
+            Err(DomainError::Execution(_))
+        ));
+    }
+}
@@ -0,0 +1,415 @@
+//! 基于 JSON 文件的规则仓库
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{CrawlerRule, DomainError, FlowType, MediaType, Result};
+use crate::ports::CrawlerRuleRepository;
+
+const INDEX_FILE: &str = "index.json";
+
+/// `index.json` 中单条规则的摘要，查询时无需解析规则文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    name: String,
+    media_type: MediaType,
+    #[serde(default = "enabled_default")]
+    enabled: bool,
+    #[serde(default)]
+    flows: Vec<FlowType>,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    next_id: i64,
+    rules: BTreeMap<i64, IndexEntry>,
+}
+
+/// 将每条规则保存为目录下的 `{id}.json`，便于用户直接管理规则文件
+///
+/// 目录中的 `index.json` 记录 ID 分配与各规则的名称、媒体类型等摘要；
+/// 索引常驻内存，所有读写都在同一把锁内完成，文件以先写临时文件再重命名的方式替换。
+#[derive(Debug)]
+pub struct FileCrawlerRuleRepository {
+    dir: PathBuf,
+    index: Mutex<Index>,
+}
+
+impl FileCrawlerRuleRepository {
+    /// 打开规则目录，不存在时创建
+    ///
+    /// 索引中出现小于 1 或无法再分配后继的规则 ID 时返回 [`DomainError::Storage`]。
+    /// 手工编辑过的索引中 `next_id` 可能落后于已有的规则 ID，打开时会调整到最大
+    /// ID 之后，避免新规则覆盖已有文件。
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        let index_path = dir.join(INDEX_FILE);
+        let index = if index_path.exists() {
+            let content = fs::read_to_string(&index_path).map_err(|e| io_error(&index_path, e))?;
+            let mut index: Index = serde_json::from_str(&content)?;
+            if let Some(id) = index.rules.keys().find(|id| **id < 1) {
+                return Err(DomainError::Storage(format!(
+                    "{}: 规则 ID {id} 无效，必须为正整数",
+                    index_path.display()
+                )));
+            }
+            if let Some(&max_id) = index.rules.keys().next_back() {
+                let next_id = max_id.checked_add(1).ok_or_else(|| {
+                    DomainError::Storage(format!("{}: 规则 ID {max_id} 过大", index_path.display()))
+                })?;
+                index.next_id = index.next_id.max(next_id);
+            }
+            index
+        } else {
+            Index::default()
+        };
+        Ok(Self {
+            dir,
+            index: Mutex::new(index),
+        })
+    }
+
+    fn index(&self) -> MutexGuard<'_, Index> {
+        self.index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn rule_path(&self, id: i64) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    /// 读取规则文件，旧格式经 [`CrawlerRule::migrate`] 升级
+    fn read_rule(&self, id: i64) -> Result<CrawlerRule> {
+        let path = self.rule_path(id);
+        let content = fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
+        let mut rule = CrawlerRule::from_json_str(&content)?;
+        rule.id = Some(id);
+        Ok(rule)
+    }
+
+    fn write_index(&self, index: &Index) -> Result<()> {
+        write_atomic(
+            &self.dir.join(INDEX_FILE),
+            &serde_json::to_string_pretty(index)?,
+        )
+    }
+
+    /// 读取索引中满足条件的规则，按 ID 升序
+    fn find_where(&self, filter: impl Fn(&IndexEntry) -> bool) -> Result<Vec<CrawlerRule>> {
+        let index = self.index();
+        index
+            .rules
+            .iter()
+            .filter(|(_, entry)| filter(entry))
+            .map(|(&id, _)| self.read_rule(id))
+            .collect()
+    }
+
+    fn set_enabled(&self, id: i64, enabled: bool) -> Result<bool> {
+        let mut index = self.index();
+        let Some(entry) = index.rules.get_mut(&id) else {
+            return Ok(false);
+        };
+        entry.enabled = enabled;
+        self.write_index(&index)?;
+        Ok(true)
+    }
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> DomainError {
+    DomainError::Storage(format!("{}: {err}", path.display()))
+}
+
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, content).map_err(|e| io_error(&tmp, e))?;
+    fs::rename(&tmp, path).map_err(|e| io_error(path, e))
+}
+
+#[async_trait]
+impl CrawlerRuleRepository for FileCrawlerRuleRepository {
+    async fn save(&self, rule: &CrawlerRule) -> Result<i64> {
+        let mut index = self.index();
+        let id = match rule.id {
+            Some(id) => id,
+            None => index.next_id.max(1),
+        };
+        if id < 1 {
+            return Err(DomainError::InvalidRule(format!(
+                "规则 ID {id} 无效，必须为正整数"
+            )));
+        }
+        let next_id = id
+            .checked_add(1)
+            .ok_or_else(|| DomainError::InvalidRule(format!("规则 ID {id} 过大")))?;
+        index.next_id = index.next_id.max(next_id);
+
+        let mut stored = rule.clone();
+        stored.id = None;
+        stored.flows.sort_connections();
+        write_atomic(&self.rule_path(id), &serde_json::to_string_pretty(&stored)?)?;
+
+        let enabled = index.rules.get(&id).is_none_or(|entry| entry.enabled);
+        let flows = rule
+            .flows
+            .defined_flows()
+            .into_iter()
+            .map(|(t, _)| t)
+            .collect();
+        index.rules.insert(
+            id,
+            IndexEntry {
+                name: rule.name.clone(),
+                media_type: rule.media_type.clone(),
+                enabled,
+                flows,
+            },
+        );
+        self.write_index(&index)?;
+        Ok(id)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<CrawlerRule>> {
+        let index = self.index();
+        if !index.rules.contains_key(&id) {
+            return Ok(None);
+        }
+        self.read_rule(id).map(Some)
+    }
+
+    async fn count(&self) -> Result<usize> {
+        Ok(self.index().rules.len())
+    }
+
+    async fn find_all(&self) -> Result<Vec<CrawlerRule>> {
+        self.find_where(|_| true)
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Vec<CrawlerRule>> {
+        self.find_where(|entry| entry.name == name)
+    }
+
+    async fn find_by_media_type(&self, media_type: MediaType) -> Result<Vec<CrawlerRule>> {
+        self.find_where(|entry| entry.media_type == media_type)
+    }
+
+    async fn find_with_flow_type(&self, flow_type: FlowType) -> Result<Vec<CrawlerRule>> {
+        self.find_where(|entry| entry.flows.contains(&flow_type))
+    }
+
+    async fn find_enabled(&self) -> Result<Vec<CrawlerRule>> {
+        self.find_where(|entry| entry.enabled)
+    }
+
+    async fn disable(&self, id: i64) -> Result<bool> {
+        self.set_enabled(id, false)
+    }
+
+    async fn enable(&self, id: i64) -> Result<bool> {
+        self.set_enabled(id, true)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        let mut index = self.index();
+        if index.rules.remove(&id).is_none() {
+            return Ok(false);
+        }
+        self.write_index(&index)?;
+        let path = self.rule_path(id);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(&path, e)),
+            _ => Ok(true),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::graph::test_support::chain_graph;
+    use crate::domain::{Flow, Flows};
+
+    fn names(rules: &[CrawlerRule]) -> Vec<&str> {
+        rules.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn save_rejects_out_of_range_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = FileCrawlerRuleRepository::open(dir.path()).unwrap();
+        for id in [0, -3, i64::MAX] {
+            let mut rule = CrawlerRule::new("bad", MediaType::Video);
+            rule.id = Some(id);
+            assert!(
+                matches!(repo.save(&rule).await, Err(DomainError::InvalidRule(_))),
+                "{id}"
+            );
+        }
+        assert!(repo.find_all().await.unwrap().is_empty());
+        assert_eq!(
+            repo.save(&CrawlerRule::new("ok", MediaType::Video))
+                .await
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn open_rejects_non_positive_ids_in_index() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(INDEX_FILE),
+            r#"{ "next_id": 1, "rules": { "0": { "name": "x", "media_type": "video" } } }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            FileCrawlerRuleRepository::open(dir.path()),
+            Err(DomainError::Storage(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn open_advances_stale_next_id_past_existing_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(INDEX_FILE),
+            r#"{ "next_id": 1, "rules": { "1": { "name": "x", "media_type": "video" } } }"#,
+        )
+        .unwrap();
+        let repo = FileCrawlerRuleRepository::open(dir.path()).unwrap();
+        let id = repo
+            .save(&CrawlerRule::new("new", MediaType::Video))
+            .await
+            .unwrap();
+        assert_eq!(id, 2);
+        assert_eq!(repo.index().rules[&1].name, "x");
+
+        std::fs::write(
+            dir.path().join(INDEX_FILE),
+            format!(
+                r#"{{ "next_id": 1, "rules": {{ "{}": {{ "name": "x", "media_type": "video" }} }} }}"#,
+                i64::MAX
+            ),
+        )
+        .unwrap();
+        assert!(matches!(
+            FileCrawlerRuleRepository::open(dir.path()),
+            Err(DomainError::Storage(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn round_trips_rules_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let search = Flow::new(FlowType::Search, chain_graph(&["a"], &[]));
+        let rule = CrawlerRule::new("video", MediaType::Video)
+            .with_flows(Flows::new().with_search(search));
+
+        let (first, second) = {
+            let repo = FileCrawlerRuleRepository::open(dir.path()).unwrap();
+            let first = repo.save(&rule).await.unwrap();
+            let second = repo
+                .save(&CrawlerRule::new("novel", MediaType::Novel))
+                .await
+                .unwrap();
+            repo.disable(second).await.unwrap();
+            (first, second)
+        };
+        assert_eq!((first, second), (1, 2));
+        assert!(dir.path().join("1.json").exists());
+
+        let repo = FileCrawlerRuleRepository::open(dir.path()).unwrap();
+        let all = repo.find_all().await.unwrap();
+        assert_eq!(names(&all), vec!["video", "novel"]);
+        assert_eq!(all[0].id, Some(first));
+        assert_eq!(all[0].flows, rule.flows);
+        assert_eq!(names(&repo.find_enabled().await.unwrap()), vec!["video"]);
+        assert_eq!(
+            names(&repo.find_with_flow_type(FlowType::Search).await.unwrap()),
+            vec!["video"]
+        );
+        assert_eq!(
+            names(&repo.find_by_media_type(MediaType::Novel).await.unwrap()),
+            vec!["novel"]
+        );
+
+        // 删除后 ID 不会被重新分配
+        assert!(repo.delete(second).await.unwrap());
+        let third = repo
+            .save(&CrawlerRule::new("music", MediaType::Music))
+            .await
+            .unwrap();
+        assert_eq!(third, 3);
+        assert!(!dir.path().join("2.json").exists());
+    }
+
+    #[tokio::test]
+    async fn rule_files_load_through_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = FileCrawlerRuleRepository::open(dir.path()).unwrap();
+        let id = repo
+            .save(&CrawlerRule::new("future", MediaType::Video))
+            .await
+            .unwrap();
+        fs::write(
+            repo.rule_path(id),
+            r#"{"schema_version":999,"name":"future","media_type":"video"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            repo.find_by_id(id).await,
+            Err(DomainError::InvalidRule(_))
+        ));
+
+        fs::write(
+            repo.rule_path(id),
+            r#"{"name":"legacy","media_type":"video"}"#,
+        )
+        .unwrap();
+        let legacy = repo.find_by_id(id).await.unwrap().unwrap();
+        assert_eq!(legacy.schema_version, crate::domain::CURRENT_SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn save_with_id_updates_existing_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = FileCrawlerRuleRepository::open(dir.path()).unwrap();
+        let id = repo
+            .save(&CrawlerRule::new("old", MediaType::General))
+            .await
+            .unwrap();
+        let mut rule = repo.find_by_id(id).await.unwrap().unwrap();
+        rule.name = "new".to_string();
+        assert_eq!(repo.save(&rule).await.unwrap(), id);
+
+        assert!(repo.find_by_name("old").await.unwrap().is_empty());
+        assert_eq!(names(&repo.find_by_name("new").await.unwrap()), vec!["new"]);
+        assert_eq!(repo.find_by_id(99).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn save_writes_sorted_connections() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = FileCrawlerRuleRepository::open(dir.path()).unwrap();
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.connections.reverse();
+        let rule = CrawlerRule::new("video", MediaType::Video)
+            .with_flows(Flows::new().with_search(Flow::new(FlowType::Search, graph.clone())));
+        let id = repo.save(&rule).await.unwrap();
+
+        let found = repo.find_by_id(id).await.unwrap().unwrap();
+        graph.sort_connections();
+        assert_eq!(found.flows.get(FlowType::Search).unwrap().graph, graph);
+    }
+}
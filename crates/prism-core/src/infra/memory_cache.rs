@@ -0,0 +1,196 @@
+//! 基于内存的缓存
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::domain::Result;
+use crate::ports::CacheStore;
+
+/// 进程内缓存，用于测试与默认运行时
+///
+/// 过期时间在 `get` 时检查，过期的键会被顺带移除；数据不会持久化。
+#[derive(Debug, Default)]
+pub struct InMemoryCacheStore {
+    entries: Mutex<HashMap<String, (Value, Option<Instant>)>>,
+}
+
+impl InMemoryCacheStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Value, Option<Instant>)>> {
+        // 持锁期间不会 panic，中毒时数据仍一致，直接沿用
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[async_trait]
+impl CacheStore for InMemoryCacheStore {
+    async fn get(&self, key: &str) -> Result<Option<Value>> {
+        Ok(live_value(&mut self.entries(), key, Instant::now()))
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<()> {
+        let expires_at = ttl.and_then(|ttl| Instant::now().checked_add(ttl));
+        self.entries().insert(key.to_string(), (value, expires_at));
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<bool> {
+        Ok(self.entries().remove(key).is_some())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.entries().clear();
+        Ok(())
+    }
+
+    /// 在一次加锁内完成全部写入
+    async fn set_many(&self, items: &[(String, Value, Option<Duration>)]) -> Result<()> {
+        let now = Instant::now();
+        let mut entries = self.entries();
+        for (key, value, ttl) in items {
+            entries.insert(
+                key.clone(),
+                (value.clone(), ttl.and_then(|ttl| now.checked_add(ttl))),
+            );
+        }
+        Ok(())
+    }
+
+    /// 在一次加锁内完成全部读取
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<Value>>> {
+        let now = Instant::now();
+        let mut entries = self.entries();
+        Ok(keys
+            .iter()
+            .map(|key| live_value(&mut entries, key, now))
+            .collect())
+    }
+}
+
+/// 读取未过期的值；已过期的键顺带移除
+fn live_value(
+    entries: &mut HashMap<String, (Value, Option<Instant>)>,
+    key: &str,
+    now: Instant,
+) -> Option<Value> {
+    let expired = match entries.get(key) {
+        None => return None,
+        Some((_, expires_at)) => expires_at.is_some_and(|at| at <= now),
+    };
+    if expired {
+        entries.remove(key);
+        return None;
+    }
+    entries.get(key).map(|(value, _)| value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn set_get_remove_and_clear() {
+        let cache = InMemoryCacheStore::new();
+        cache.set("a", json!({ "n": 1 }), None).await.unwrap();
+        cache.set("b", json!("x"), None).await.unwrap();
+        assert_eq!(cache.get("a").await.unwrap(), Some(json!({ "n": 1 })));
+        assert_eq!(cache.get("missing").await.unwrap(), None);
+
+        assert!(cache.remove("a").await.unwrap());
+        assert!(!cache.remove("a").await.unwrap());
+
+        cache.clear().await.unwrap();
+        assert_eq!(cache.get("b").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn get_many_preserves_key_order_including_misses() {
+        let cache = InMemoryCacheStore::new();
+        cache
+            .set_many(&[
+                ("a".into(), json!(1), None),
+                ("b".into(), json!(2), None),
+                ("a".into(), json!(3), None),
+            ])
+            .await
+            .unwrap();
+        let keys: Vec<String> = ["b", "missing", "a"].map(String::from).to_vec();
+        assert_eq!(
+            cache.get_many(&keys).await.unwrap(),
+            vec![Some(json!(2)), None, Some(json!(3))]
+        );
+    }
+
+    #[tokio::test]
+    async fn default_batch_methods_delegate_to_single_key_ops() {
+        /// 只实现单键方法的缓存，批量方法使用 trait 默认实现
+        struct SingleKey(InMemoryCacheStore);
+
+        #[async_trait]
+        impl CacheStore for SingleKey {
+            async fn get(&self, key: &str) -> Result<Option<Value>> {
+                self.0.get(key).await
+            }
+            async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<()> {
+                self.0.set(key, value, ttl).await
+            }
+            async fn remove(&self, key: &str) -> Result<bool> {
+                self.0.remove(key).await
+            }
+            async fn clear(&self) -> Result<()> {
+                self.0.clear().await
+            }
+        }
+
+        let cache = SingleKey(InMemoryCacheStore::new());
+        cache
+            .set_many(&[("x".into(), json!("x"), None)])
+            .await
+            .unwrap();
+        let keys = vec!["missing".to_string(), "x".to_string()];
+        assert_eq!(
+            cache.get_many(&keys).await.unwrap(),
+            vec![None, Some(json!("x"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_evicted_on_get() {
+        let cache = InMemoryCacheStore::new();
+        cache
+            .set("short", json!(1), Some(Duration::from_millis(20)))
+            .await
+            .unwrap();
+        cache.set("forever", json!(2), None).await.unwrap();
+        assert_eq!(cache.get("short").await.unwrap(), Some(json!(1)));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(cache.get("short").await.unwrap(), None);
+        assert!(!cache.entries().contains_key("short"));
+        assert_eq!(cache.get("forever").await.unwrap(), Some(json!(2)));
+    }
+
+    #[tokio::test]
+    async fn unrepresentable_ttl_never_expires() {
+        let cache = InMemoryCacheStore::new();
+        cache.set("a", json!(1), Some(Duration::MAX)).await.unwrap();
+        cache
+            .set_many(&[("b".to_string(), json!(2), Some(Duration::MAX))])
+            .await
+            .unwrap();
+        assert_eq!(cache.get("a").await.unwrap(), Some(json!(1)));
+        assert_eq!(cache.get("b").await.unwrap(), Some(json!(2)));
+        assert_eq!(cache.entries().get("a").unwrap().1, None);
+    }
+}
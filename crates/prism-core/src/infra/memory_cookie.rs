@@ -0,0 +1,161 @@
+//! 基于内存的 Cookie 存储
+
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::domain::Result;
+use crate::ports::{Cookie, CookieStore};
+
+/// 进程内 Cookie 存储，用于测试与默认运行时
+#[derive(Debug, Default)]
+pub struct InMemoryCookieStore {
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+impl InMemoryCookieStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn cookies(&self) -> MutexGuard<'_, Vec<Cookie>> {
+        self.cookies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 比较域名时忽略大小写与前导 `.`
+fn same_domain(a: &str, b: &str) -> bool {
+    a.trim_start_matches('.')
+        .eq_ignore_ascii_case(b.trim_start_matches('.'))
+}
+
+#[async_trait]
+impl CookieStore for InMemoryCookieStore {
+    async fn set(&self, cookie: Cookie) -> Result<()> {
+        let mut cookies = self.cookies();
+        cookies.retain(|c| {
+            !(c.name == cookie.name
+                && c.path == cookie.path
+                && same_domain(&c.domain, &cookie.domain))
+        });
+        cookies.push(cookie);
+        Ok(())
+    }
+
+    async fn get_all_for_domain(&self, domain: &str) -> Result<Vec<Cookie>> {
+        let now = Utc::now();
+        Ok(self
+            .cookies()
+            .iter()
+            .filter(|c| c.matches_domain(domain) && !c.is_expired_at(now))
+            .cloned()
+            .collect())
+    }
+
+    async fn remove(&self, domain: &str, name: &str) -> Result<usize> {
+        let mut cookies = self.cookies();
+        let before = cookies.len();
+        cookies.retain(|c| !(c.name == name && same_domain(&c.domain, domain)));
+        Ok(before - cookies.len())
+    }
+
+    async fn clear_expired(&self) -> Result<usize> {
+        let now = Utc::now();
+        let mut cookies = self.cookies();
+        let before = cookies.len();
+        cookies.retain(|c| !c.is_expired_at(now));
+        Ok(before - cookies.len())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.cookies().clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn names(cookies: &[Cookie]) -> Vec<&str> {
+        cookies.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn parent_domain_cookie_matches_subdomain_request() {
+        let store = InMemoryCookieStore::new();
+        store
+            .set(Cookie::new("root", "1", "example.com"))
+            .await
+            .unwrap();
+        store
+            .set(Cookie::new("dotted", "2", ".example.com"))
+            .await
+            .unwrap();
+        store
+            .set(Cookie::new("api", "3", "api.example.com"))
+            .await
+            .unwrap();
+        store
+            .set(Cookie::new("other", "4", "example.org"))
+            .await
+            .unwrap();
+
+        let cookies = store.get_all_for_domain("api.example.com").await.unwrap();
+        assert_eq!(names(&cookies), vec!["root", "dotted", "api"]);
+        let cookies = store.get_all_for_domain("example.com").await.unwrap();
+        assert_eq!(names(&cookies), vec!["root", "dotted"]);
+    }
+
+    #[tokio::test]
+    async fn set_replaces_same_cookie() {
+        let store = InMemoryCookieStore::new();
+        store
+            .set(Cookie::new("sid", "old", "example.com"))
+            .await
+            .unwrap();
+        store
+            .set(Cookie::new("sid", "new", ".example.com"))
+            .await
+            .unwrap();
+        let cookies = store.get_all_for_domain("example.com").await.unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].value, "new");
+
+        assert_eq!(store.remove("example.com", "sid").await.unwrap(), 1);
+        assert!(store
+            .get_all_for_domain("example.com")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn clear_expired_purges_only_expired_cookies() {
+        let store = InMemoryCookieStore::new();
+        let now = Utc::now();
+        store
+            .set(Cookie::new("old", "1", "example.com").with_expires(now - Duration::hours(1)))
+            .await
+            .unwrap();
+        store
+            .set(Cookie::new("fresh", "2", "example.com").with_expires(now + Duration::hours(1)))
+            .await
+            .unwrap();
+        store
+            .set(Cookie::new("session", "3", "example.com"))
+            .await
+            .unwrap();
+
+        let cookies = store.get_all_for_domain("example.com").await.unwrap();
+        assert_eq!(names(&cookies), vec!["fresh", "session"]);
+        assert_eq!(store.clear_expired().await.unwrap(), 1);
+        assert_eq!(store.clear_expired().await.unwrap(), 0);
+        assert_eq!(store.cookies().len(), 2);
+    }
+}
@@ -0,0 +1,15 @@
+//! 端口的具体实现
+
+mod file_rule_repository;
+mod memory_cache;
+mod memory_cookie;
+mod reqwest_http_client;
+mod rhai_engine;
+mod sqlite_rule_repository;
+
+pub use file_rule_repository::FileCrawlerRuleRepository;
+pub use memory_cache::InMemoryCacheStore;
+pub use memory_cookie::InMemoryCookieStore;
+pub use reqwest_http_client::ReqwestHttpClient;
+pub use rhai_engine::RhaiScriptEngine;
+pub use sqlite_rule_repository::SqliteCrawlerRuleRepository;
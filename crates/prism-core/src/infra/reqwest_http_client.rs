@@ -0,0 +1,310 @@
+//! 基于 reqwest 的 HTTP 客户端
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Method, Proxy};
+
+use crate::domain::config::normalized_headers;
+use crate::domain::{DomainError, HttpConfig, Result, RetryPolicy};
+use crate::ports::{HttpClient, HttpResponse};
+
+/// 按 [`HttpConfig`] 构建的 HTTP 客户端
+///
+/// 超时、User-Agent、默认请求头与代理在构建时固定，每次请求传入的请求头覆盖
+/// 同名的默认请求头。状态码在 [`RetryPolicy::retry_on_status`] 中时按重试策略
+/// 等待后重试，重试耗尽后返回最后一次的结果；连接失败或超时只对幂等方法重试，
+/// 因为非幂等请求可能已被服务端处理，见 [`ReqwestHttpClient::with_retry_non_idempotent`]。
+#[derive(Debug, Clone)]
+pub struct ReqwestHttpClient {
+    client: Client,
+    retry: RetryPolicy,
+    retry_non_idempotent: bool,
+}
+
+impl ReqwestHttpClient {
+    /// 配置无法应用（如请求头或代理地址非法）时返回 [`DomainError::InvalidRule`]
+    pub fn new(config: &HttpConfig) -> Result<Self> {
+        let invalid = |e: String| DomainError::InvalidRule(format!("HTTP 配置无效: {e}"));
+
+        let mut builder =
+            Client::builder().default_headers(header_map(&normalized_headers(&config.headers))?);
+        if let Some(timeout_ms) = config.timeout_ms {
+            builder = builder.timeout(std::time::Duration::from_millis(timeout_ms));
+        }
+        if let Some(user_agent) = &config.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(proxy_config) = &config.proxy {
+            let mut proxy = Proxy::all(&proxy_config.url).map_err(|e| invalid(e.to_string()))?;
+            if let Some(username) = &proxy_config.username {
+                proxy = proxy.basic_auth(username, proxy_config.password.as_deref().unwrap_or(""));
+            }
+            builder = builder.proxy(proxy);
+        }
+        let client = builder.build().map_err(|e| invalid(e.to_string()))?;
+        Ok(Self {
+            client,
+            retry: config.retry.clone(),
+            retry_non_idempotent: false,
+        })
+    }
+
+    /// 连接失败或超时后是否也重试 POST、PATCH 等非幂等请求，默认不重试
+    pub fn with_retry_non_idempotent(mut self, retry: bool) -> Self {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    async fn send_once(
+        &self,
+        method: &Method,
+        url: &str,
+        headers: &HeaderMap,
+        body: Option<&[u8]>,
+    ) -> reqwest::Result<HttpResponse> {
+        let mut request = self
+            .client
+            .request(method.clone(), url)
+            .headers(headers.clone());
+        if let Some(body) = body {
+            request = request.body(body.to_vec());
+        }
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                )
+            })
+            .collect();
+        let body = response.bytes().await?.to_vec();
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+#[async_trait]
+impl HttpClient for ReqwestHttpClient {
+    async fn request(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(String, String)],
+        body: Option<&[u8]>,
+    ) -> Result<HttpResponse> {
+        let method = Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+            .map_err(|e| DomainError::Execution(format!("无效的请求方法 {method}: {e}")))?;
+        let headers = header_map(headers.iter().map(|(name, value)| (name, value)))?;
+
+        let retry_errors = self.retry_non_idempotent || is_idempotent(&method);
+        let mut attempt = 0;
+        loop {
+            let result = self.send_once(&method, url, &headers, body).await;
+            let retryable = match &result {
+                Ok(response) => self.retry.should_retry_status(response.status),
+                Err(e) => retry_errors && (e.is_timeout() || e.is_connect()),
+            };
+            if !retryable || attempt >= self.retry.max_retries as usize {
+                return result.map_err(execution_error);
+            }
+            attempt += 1;
+            tracing::debug!(url, attempt, "请求失败，准备重试");
+            tokio::time::sleep(self.retry.delay_for_attempt(attempt)).await;
+        }
+    }
+}
+
+fn header_map<'a, I>(headers: I) -> Result<HeaderMap>
+where
+    I: IntoIterator<Item = (&'a String, &'a String)>,
+{
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let invalid = |e: String| DomainError::InvalidRule(format!("请求头 {name} 无效: {e}"));
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| invalid(e.to_string()))?;
+        let value = HeaderValue::from_str(value).map_err(|e| invalid(e.to_string()))?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+/// RFC 9110 中定义为幂等的方法，重复发送不会产生额外的副作用
+fn is_idempotent(method: &Method) -> bool {
+    [
+        Method::GET,
+        Method::HEAD,
+        Method::PUT,
+        Method::DELETE,
+        Method::OPTIONS,
+        Method::TRACE,
+    ]
+    .contains(method)
+}
+
+fn execution_error(e: reqwest::Error) -> DomainError {
+    DomainError::Execution(format!("HTTP 请求失败: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sends_configured_user_agent_and_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/list"))
+            .and(header("user-agent", "Prism/1.0"))
+            .and(header("referer", "https://a.com"))
+            .and(header("x-token", "t"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ReqwestHttpClient::new(&HttpConfig {
+            user_agent: Some("Prism/1.0".into()),
+            headers: HashMap::from([("Referer".into(), "https://a.com".into())]),
+            ..HttpConfig::default()
+        })
+        .unwrap();
+        let response = client
+            .request(
+                "get",
+                &format!("{}/list", server.uri()),
+                &[("X-Token".into(), "t".into())],
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.text(), "ok");
+    }
+
+    #[tokio::test]
+    async fn retries_on_configured_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let retry = RetryPolicy {
+            delay_ms: 1,
+            ..RetryPolicy::default()
+        };
+        let client = ReqwestHttpClient::new(&HttpConfig {
+            retry: retry.clone(),
+            ..HttpConfig::default()
+        })
+        .unwrap();
+        assert_eq!(client.get(&server.uri()).await.unwrap().status, 200);
+
+        let client = ReqwestHttpClient::new(&HttpConfig {
+            retry: RetryPolicy {
+                max_retries: 0,
+                ..retry
+            },
+            ..HttpConfig::default()
+        })
+        .unwrap();
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        assert_eq!(client.get(&server.uri()).await.unwrap().status, 503);
+    }
+
+    #[tokio::test]
+    async fn timeouts_retry_only_idempotent_methods_by_default() {
+        let server = MockServer::start().await;
+        let slow = ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(500));
+        Mock::given(method("GET"))
+            .respond_with(slow.clone())
+            .expect(3)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/once"))
+            .respond_with(slow.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/opt-in"))
+            .respond_with(slow)
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let config = HttpConfig {
+            timeout_ms: Some(50),
+            retry: RetryPolicy {
+                max_retries: 2,
+                delay_ms: 1,
+                ..RetryPolicy::default()
+            },
+            ..HttpConfig::default()
+        };
+        let client = ReqwestHttpClient::new(&config).unwrap();
+        assert!(client.get(&server.uri()).await.is_err());
+        let url = format!("{}/once", server.uri());
+        assert!(client.request("POST", &url, &[], None).await.is_err());
+
+        let client = client.with_retry_non_idempotent(true);
+        let url = format!("{}/opt-in", server.uri());
+        assert!(client.request("POST", &url, &[], None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn case_variant_default_headers_resolve_deterministically() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("x-token", "lower"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ReqwestHttpClient::new(&HttpConfig {
+            headers: HashMap::from([
+                ("X-Token".into(), "upper".into()),
+                ("x-token".into(), "lower".into()),
+            ]),
+            ..HttpConfig::default()
+        })
+        .unwrap();
+        assert_eq!(client.get(&server.uri()).await.unwrap().status, 200);
+    }
+
+    #[test]
+    fn rejects_invalid_header_config() {
+        let config = HttpConfig {
+            headers: HashMap::from([("bad header".into(), "x".into())]),
+            ..HttpConfig::default()
+        };
+        assert!(matches!(
+            ReqwestHttpClient::new(&config),
+            Err(DomainError::InvalidRule(_))
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+//! 基于 Rhai 的脚本引擎
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use rhai::{Dynamic, Engine, EvalAltResult, Scope};
+use serde_json::Value;
+
+use crate::domain::{DomainError, Result};
+use crate::ports::{ScriptEngine, ScriptEngineType};
+
+/// 默认的单次执行操作数上限
+const DEFAULT_MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Rhai 脚本引擎
+///
+/// 上下文以 `context` 变量暴露给脚本：JSON 对象转为 Rhai 对象映射；文本形式的上下文
+/// 能解析为 JSON 时同样转换，否则为字符串。
+/// 脚本在阻塞线程中执行，并受操作数上限约束，避免死循环永久占用线程。
+#[derive(Debug, Clone)]
+pub struct RhaiScriptEngine {
+    max_operations: u64,
+}
+
+impl Default for RhaiScriptEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RhaiScriptEngine {
+    pub fn new() -> Self {
+        Self {
+            max_operations: DEFAULT_MAX_OPERATIONS,
+        }
+    }
+
+    /// 设置单次执行的操作数上限，`0` 表示不限制
+    pub fn with_max_operations(mut self, max_operations: u64) -> Self {
+        self.max_operations = max_operations;
+        self
+    }
+
+    async fn run(&self, script: &str, context: Value, deadline: Option<Instant>) -> Result<Value> {
+        let script = script.to_string();
+        let max_operations = self.max_operations;
+        tokio::task::spawn_blocking(move || eval(&script, &context, max_operations, deadline))
+            .await
+            .map_err(|e| DomainError::Execution(format!("脚本线程异常退出: {e}")))?
+    }
+}
+
+#[async_trait]
+impl ScriptEngine for RhaiScriptEngine {
+    fn engine_type(&self) -> ScriptEngineType {
+        ScriptEngineType::Rhai
+    }
+
+    async fn execute(&self, script: &str, context: &str) -> Result<String> {
+        let context = serde_json::from_str(context).unwrap_or_else(|_| Value::from(context));
+        match self.run(script, context, None).await? {
+            Value::String(text) => Ok(text),
+            other => Ok(other.to_string()),
+        }
+    }
+
+    /// 除了竞争计时外，还在 Rhai 的进度回调中检查截止时间，
+    /// 超时后脚本线程会自行终止而不是继续空转；`timeout` 大到无法表示截止时间
+    /// 时不设进度回调
+    async fn execute_with_timeout(
+        &self,
+        script: &str,
+        context: &Value,
+        timeout: Duration,
+    ) -> Result<Value> {
+        let deadline = Instant::now().checked_add(timeout);
+        tokio::select! {
+            result = self.run(script, context.clone(), deadline) => result,
+            _ = tokio::time::sleep(timeout) => Err(timeout_error()),
+        }
+    }
+}
+
+fn timeout_error() -> DomainError {
+    DomainError::Execution("script timeout".to_string())
+}
+
+fn eval(
+    script: &str,
+    context: &Value,
+    max_operations: u64,
+    deadline: Option<Instant>,
+) -> Result<Value> {
+    let mut engine = Engine::new();
+    engine.set_max_operations(max_operations);
+    if let Some(deadline) = deadline {
+        engine.on_progress(move |_| (Instant::now() >= deadline).then_some(Dynamic::UNIT));
+    }
+
+    let context_value = rhai::serde::to_dynamic(context)
+        .map_err(|e| DomainError::Execution(format!("上下文转换失败: {e}")))?;
+    let mut scope = Scope::new();
+    scope.push("context", context_value);
+
+    let result = engine
+        .eval_with_scope::<Dynamic>(&mut scope, script)
+        .map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) => timeout_error(),
+            other => DomainError::Execution(other.to_string()),
+        })?;
+
+    rhai::serde::from_dynamic(&result)
+        .map_err(|e| DomainError::Execution(format!("脚本结果无法序列化: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn exposes_context_to_script() {
+        let engine = RhaiScriptEngine::new();
+        let result = engine
+            .execute(r#"context.name + "!""#, r#"{ "name": "prism" }"#)
+            .await
+            .unwrap();
+        assert_eq!(result, "prism!");
+
+        let result = engine
+            .execute("context.n * 2", r#"{ "n": 21 }"#)
+            .await
+            .unwrap();
+        assert_eq!(result, "42");
+    }
+
+    #[tokio::test]
+    async fn typed_execution_reads_context_map() {
+        let engine = RhaiScriptEngine::new();
+        let result = engine
+            .execute_with_timeout(
+                r#"#{ title: context.item.title, pages: context.item.pages + 1 }"#,
+                &json!({ "item": { "title": "prism", "pages": 41 } }),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "title": "prism", "pages": 42 }));
+
+        let text = engine
+            .execute_with_timeout(r#""42""#, &Value::Null, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(text, json!("42"));
+    }
+
+    #[tokio::test]
+    async fn infinite_loop_times_out() {
+        let engine = RhaiScriptEngine::new().with_max_operations(0);
+        let started = Instant::now();
+        let err = engine
+            .execute_with_timeout("loop {}", &json!({}), Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Execution(ref msg) if msg == "script timeout"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn huge_timeout_runs_without_deadline() {
+        let engine = RhaiScriptEngine::new();
+        let value = engine
+            .execute_with_timeout("1 + 2", &json!({}), Duration::MAX)
+            .await
+            .unwrap();
+        assert_eq!(value, json!(3));
+    }
+
+    #[tokio::test]
+    async fn operation_limit_stops_runaway_script() {
+        let engine = RhaiScriptEngine::new().with_max_operations(1_000);
+        let err = engine.execute("loop {}", "{}").await.unwrap_err();
+        assert!(matches!(err, DomainError::Execution(_)));
+    }
+
+    #[tokio::test]
+    async fn default_timeout_races_slow_execution() {
+        struct Sleepy;
+
+        #[async_trait]
+        impl ScriptEngine for Sleepy {
+            fn engine_type(&self) -> ScriptEngineType {
+                ScriptEngineType::JavaScript
+            }
+
+            async fn execute(&self, _script: &str, _context: &str) -> Result<String> {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(String::new())
+            }
+        }
+
+        let err = Sleepy
+            .execute_with_timeout("", &Value::Null, Duration::from_millis(20))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Execution(ref msg) if msg == "script timeout"));
+    }
+}
@@ -0,0 +1,362 @@
+//! 基于 SQLite 的规则仓库
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, ToSql};
+
+use crate::domain::{CrawlerRule, DomainError, FlowType, MediaType, Result};
+use crate::ports::CrawlerRuleRepository;
+
+/// 按顺序执行的迁移脚本，已执行到的版本记录在 `PRAGMA user_version` 中
+const MIGRATIONS: &[&str] = &[include_str!(
+    "../../migrations/0001_create_crawler_rules.sql"
+)];
+
+/// 将规则以 JSON 保存在 SQLite 中
+///
+/// 名称、媒体类型与启用状态另存为带索引的列以便查询。查询在持锁期间同步执行，
+/// 单条规则的读写足够快，不必切换到阻塞线程。
+#[derive(Debug)]
+pub struct SqliteCrawlerRuleRepository {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteCrawlerRuleRepository {
+    /// 打开（或创建）数据库文件并执行迁移
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::from_connection(Connection::open(path).map_err(storage_error)?)
+    }
+
+    /// 内存数据库，用于测试
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory().map_err(storage_error)?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self> {
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 查询满足 `filter` 的规则，按 ID 升序
+    fn query(&self, filter: &str, args: &[&dyn ToSql]) -> Result<Vec<CrawlerRule>> {
+        self.query_sorted(filter, "", args)
+    }
+
+    /// 同 [`Self::query`]，`tail` 附加在 `ORDER BY` 之后（如 `LIMIT`）
+    fn query_sorted(
+        &self,
+        filter: &str,
+        tail: &str,
+        args: &[&dyn ToSql],
+    ) -> Result<Vec<CrawlerRule>> {
+        let conn = self.conn();
+        let sql = format!("SELECT id, rule_json FROM crawler_rules {filter} ORDER BY id {tail}");
+        let mut stmt = conn.prepare(&sql).map_err(storage_error)?;
+        let rows = stmt
+            .query_map(params_from_iter(args), |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(storage_error)?;
+        rows.map(|row| {
+            let (id, json) = row.map_err(storage_error)?;
+            decode(id, &json)
+        })
+        .collect()
+    }
+
+    fn set_enabled(&self, id: i64, enabled: bool) -> Result<bool> {
+        let changed = self
+            .conn()
+            .execute(
+                "UPDATE crawler_rules SET enabled = ?1 WHERE id = ?2",
+                params![enabled, id],
+            )
+            .map_err(storage_error)?;
+        Ok(changed > 0)
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(storage_error)?;
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = conn.transaction().map_err(storage_error)?;
+        tx.execute_batch(sql).map_err(storage_error)?;
+        tx.pragma_update(None, "user_version", index as i64 + 1)
+            .map_err(storage_error)?;
+        tx.commit().map_err(storage_error)?;
+    }
+    Ok(())
+}
+
+/// 读取存储的规则 JSON，旧格式经 [`CrawlerRule::migrate`] 升级
+fn decode(id: i64, json: &str) -> Result<CrawlerRule> {
+    let mut rule = CrawlerRule::from_json_str(json)?;
+    rule.id = Some(id);
+    Ok(rule)
+}
+
+fn storage_error(err: rusqlite::Error) -> DomainError {
+    DomainError::Storage(err.to_string())
+}
+
+#[async_trait]
+impl CrawlerRuleRepository for SqliteCrawlerRuleRepository {
+    async fn save(&self, rule: &CrawlerRule) -> Result<i64> {
+        let mut stored = rule.clone();
+        stored.id = None;
+        stored.flows.sort_connections();
+        let json = serde_json::to_string(&stored)?;
+        let conn = self.conn();
+        let id = conn
+            .query_row(
+                "INSERT INTO crawler_rules (id, name, media_type, rule_json)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (id) DO UPDATE SET
+                     name = excluded.name,
+                     media_type = excluded.media_type,
+                     rule_json = excluded.rule_json
+                 RETURNING id",
+                params![rule.id, rule.name, rule.media_type.as_str(), json],
+                |row| row.get(0),
+            )
+            .map_err(storage_error)?;
+        Ok(id)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<CrawlerRule>> {
+        let json: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT rule_json FROM crawler_rules WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(storage_error)?;
+        json.map(|json| decode(id, &json)).transpose()
+    }
+
+    async fn find_all(&self) -> Result<Vec<CrawlerRule>> {
+        self.query("", &[])
+    }
+
+    async fn count(&self) -> Result<usize> {
+        let count: i64 = self
+            .conn()
+            .query_row("SELECT COUNT(*) FROM crawler_rules", [], |row| row.get(0))
+            .map_err(storage_error)?;
+        Ok(count as usize)
+    }
+
+    async fn find_page(&self, offset: usize, limit: usize) -> Result<Vec<CrawlerRule>> {
+        let to_sql = |n: usize| i64::try_from(n).unwrap_or(i64::MAX);
+        self.query_sorted("", "LIMIT ?1 OFFSET ?2", &[&to_sql(limit), &to_sql(offset)])
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Vec<CrawlerRule>> {
+        self.query("WHERE name = ?1", &[&name])
+    }
+
+    async fn find_by_media_type(&self, media_type: MediaType) -> Result<Vec<CrawlerRule>> {
+        self.query("WHERE media_type = ?1", &[&media_type.as_str()])
+    }
+
+    async fn find_with_flow_type(&self, flow_type: FlowType) -> Result<Vec<CrawlerRule>> {
+        let path = format!("$.flows.{}", flow_type.as_str());
+        self.query("WHERE json_extract(rule_json, ?1) IS NOT NULL", &[&path])
+    }
+
+    async fn find_enabled(&self) -> Result<Vec<CrawlerRule>> {
+        self.query("WHERE enabled = 1", &[])
+    }
+
+    async fn disable(&self, id: i64) -> Result<bool> {
+        self.set_enabled(id, false)
+    }
+
+    async fn enable(&self, id: i64) -> Result<bool> {
+        self.set_enabled(id, true)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        let deleted = self
+            .conn()
+            .execute("DELETE FROM crawler_rules WHERE id = ?1", [id])
+            .map_err(storage_error)?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::graph::test_support::chain_graph;
+    use crate::domain::{Flow, Flows};
+
+    fn rule(name: &str, media_type: MediaType) -> CrawlerRule {
+        CrawlerRule::new(name, media_type)
+    }
+
+    fn names(rules: &[CrawlerRule]) -> Vec<&str> {
+        rules.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn save_stores_sorted_connections() {
+        let repo = SqliteCrawlerRuleRepository::open_in_memory().unwrap();
+        let mut graph = chain_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        graph.connections.reverse();
+        let id = repo
+            .save(
+                &rule("video", MediaType::Video).with_flows(
+                    Flows::new().with_search(Flow::new(FlowType::Search, graph.clone())),
+                ),
+            )
+            .await
+            .unwrap();
+
+        let found = repo.find_by_id(id).await.unwrap().unwrap();
+        graph.sort_connections();
+        assert_eq!(found.flows.get(FlowType::Search).unwrap().graph, graph);
+    }
+
+    #[tokio::test]
+    async fn stored_rules_load_through_migration() {
+        let repo = SqliteCrawlerRuleRepository::open_in_memory().unwrap();
+        repo.conn()
+            .execute_batch(
+                r#"INSERT INTO crawler_rules (id, name, media_type, rule_json) VALUES
+                   (1, 'legacy', 'video', '{"name":"legacy","media_type":"video"}'),
+                   (2, 'future', 'video',
+                    '{"schema_version":999,"name":"future","media_type":"video"}');"#,
+            )
+            .unwrap();
+
+        let legacy = repo.find_by_id(1).await.unwrap().unwrap();
+        assert_eq!(legacy.schema_version, crate::domain::CURRENT_SCHEMA_VERSION);
+        assert!(matches!(
+            repo.find_by_id(2).await,
+            Err(DomainError::InvalidRule(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn save_find_and_delete() {
+        let repo = SqliteCrawlerRuleRepository::open_in_memory().unwrap();
+        let video = repo.save(&rule("video", MediaType::Video)).await.unwrap();
+        let novel = repo.save(&rule("novel", MediaType::Novel)).await.unwrap();
+        assert_ne!(video, novel);
+
+        let found = repo.find_by_id(video).await.unwrap().unwrap();
+        assert_eq!(found.id, Some(video));
+        assert_eq!(found.name, "video");
+        assert_eq!(
+            names(&repo.find_all().await.unwrap()),
+            vec!["video", "novel"]
+        );
+        assert_eq!(
+            names(&repo.find_by_name("novel").await.unwrap()),
+            vec!["novel"]
+        );
+        assert_eq!(
+            names(&repo.find_by_media_type(MediaType::Video).await.unwrap()),
+            vec!["video"]
+        );
+
+        let mut renamed = found;
+        renamed.name = "movies".to_string();
+        assert_eq!(repo.save(&renamed).await.unwrap(), video);
+        assert_eq!(
+            repo.find_by_id(video).await.unwrap().unwrap().name,
+            "movies"
+        );
+
+        assert!(repo.delete(video).await.unwrap());
+        assert!(!repo.delete(video).await.unwrap());
+        assert_eq!(repo.find_by_id(video).await.unwrap(), None);
+        assert_eq!(names(&repo.find_all().await.unwrap()), vec!["novel"]);
+    }
+
+    #[tokio::test]
+    async fn disable_keeps_rule_but_hides_it_from_enabled() {
+        let repo = SqliteCrawlerRuleRepository::open_in_memory().unwrap();
+        let id = repo.save(&rule("a", MediaType::General)).await.unwrap();
+        repo.save(&rule("b", MediaType::General)).await.unwrap();
+
+        assert!(repo.disable(id).await.unwrap());
+        assert_eq!(names(&repo.find_enabled().await.unwrap()), vec!["b"]);
+        assert_eq!(repo.find_all().await.unwrap().len(), 2);
+
+        // 更新规则内容不影响启用状态
+        repo.save(&repo.find_by_id(id).await.unwrap().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(names(&repo.find_enabled().await.unwrap()), vec!["b"]);
+
+        assert!(repo.enable(id).await.unwrap());
+        assert_eq!(names(&repo.find_enabled().await.unwrap()), vec!["a", "b"]);
+        assert!(!repo.disable(999).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn find_with_flow_type_checks_defined_flows() {
+        let repo = SqliteCrawlerRuleRepository::open_in_memory().unwrap();
+        let search = Flow::new(FlowType::Search, chain_graph(&["a"], &[]));
+        repo.save(
+            &rule("with_search", MediaType::Video).with_flows(Flows::new().with_search(search)),
+        )
+        .await
+        .unwrap();
+        repo.save(&rule("empty", MediaType::Video)).await.unwrap();
+
+        let found = repo.find_with_flow_type(FlowType::Search).await.unwrap();
+        assert_eq!(names(&found), vec!["with_search"]);
+        assert!(found[0].flows.get(FlowType::Search).is_some());
+        assert!(repo
+            .find_with_flow_type(FlowType::Login)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn count_and_find_page_slice_by_id() {
+        let repo = SqliteCrawlerRuleRepository::open_in_memory().unwrap();
+        for i in 0..20 {
+            repo.save(&rule(&format!("r{i}"), MediaType::General))
+                .await
+                .unwrap();
+        }
+        assert_eq!(repo.count().await.unwrap(), 20);
+        assert_eq!(
+            names(&repo.find_page(10, 5).await.unwrap()),
+            vec!["r10", "r11", "r12", "r13", "r14"]
+        );
+        assert_eq!(repo.find_page(18, 5).await.unwrap().len(), 2);
+        assert!(repo.find_page(30, 5).await.unwrap().is_empty());
+        assert!(repo.find_page(0, 0).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn migrations_are_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        migrate(&mut conn).unwrap();
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version as usize, MIGRATIONS.len());
+    }
+}
@@ -0,0 +1,9 @@
+//! Prism 核心库
+//!
+//! 包含爬虫规则的领域模型（节点图、端口、连接等）、与之相关的校验逻辑，
+//! 按依赖顺序执行节点图的引擎，以及外部能力的端口抽象与实现。
+
+pub mod domain;
+pub mod engine;
+pub mod infra;
+pub mod ports;
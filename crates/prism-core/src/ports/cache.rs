@@ -0,0 +1,48 @@
+//! 缓存端口
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::domain::Result;
+
+/// 键值缓存，供 Cache 节点在多次执行之间保存中间结果
+///
+/// 键通常已带有命名空间前缀（见 [`crate::engine::ExecutionContext::cache_key`]）。
+#[async_trait]
+pub trait CacheStore: Send + Sync {
+    /// 读取键对应的值；不存在或已过期时返回 `None`
+    async fn get(&self, key: &str) -> Result<Option<Value>>;
+
+    /// 写入键值，`ttl` 为 `None` 表示永不过期；已存在的键会被覆盖
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<()>;
+
+    /// 删除键，返回删除前该键是否存在
+    async fn remove(&self, key: &str) -> Result<bool>;
+
+    /// 清空所有键
+    async fn clear(&self) -> Result<()>;
+
+    /// 批量写入，每项为 `(键, 值, ttl)`，语义同 [`CacheStore::set`]；同一个键出现多次时
+    /// 以最后一项为准
+    ///
+    /// 默认实现逐个调用 `set`，遇到错误即停止，此前写入的键不会回滚。
+    async fn set_many(&self, entries: &[(String, Value, Option<Duration>)]) -> Result<()> {
+        for (key, value, ttl) in entries {
+            self.set(key, value.clone(), *ttl).await?;
+        }
+        Ok(())
+    }
+
+    /// 批量读取，结果与 `keys` 一一对应、顺序相同，不存在或已过期的键为 `None`
+    ///
+    /// 默认实现逐个调用 [`CacheStore::get`]。
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<Value>>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
+    }
+}
@@ -0,0 +1,234 @@
+//! Cookie 存储端口
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::domain::Result;
+
+/// 一条 HTTP Cookie
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// 所属域名，可带前导 `.`，表示同时对子域名生效
+    pub domain: String,
+    pub path: String,
+    /// 过期时间，`None` 为会话 Cookie
+    #[serde(default)]
+    pub expires: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+}
+
+impl Cookie {
+    pub fn new(
+        name: impl Into<String>,
+        value: impl Into<String>,
+        domain: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            domain: domain.into(),
+            path: "/".to_string(),
+            expires: None,
+            secure: false,
+            http_only: false,
+        }
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    pub fn with_expires(mut self, expires: DateTime<Utc>) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// 解析响应中的 `Set-Cookie` 头
+    ///
+    /// 支持 `Domain`、`Path`、`Expires`（RFC 1123）、`Max-Age`（秒）、`Secure` 与
+    /// `HttpOnly` 属性，属性名忽略大小写，其他属性被忽略。缺少 `Domain` 时使用
+    /// `default_domain`；`Domain` 与 `default_domain` 不匹配（既不相同也不是其
+    /// 父域名）时按 RFC 6265 §5.3 拒绝整个 Cookie。同时给出 `Expires` 与
+    /// `Max-Age` 时以 `Max-Age` 为准，无法解析或超出可表示范围的日期、秒数按
+    /// RFC 6265 忽略。
+    pub fn parse_set_cookie(
+        header: &str,
+        default_domain: &str,
+    ) -> std::result::Result<Cookie, String> {
+        let mut parts = header.split(';');
+        let pair = parts.next().unwrap_or_default();
+        let (name, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("Set-Cookie 缺少 name=value: {header:?}"))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("Set-Cookie 的名称为空: {header:?}"));
+        }
+        let mut cookie = Cookie::new(name, value.trim(), default_domain);
+        let mut max_age = None;
+        for attribute in parts {
+            let (key, value) = match attribute.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => (attribute.trim(), ""),
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => cookie.domain = value.to_string(),
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "expires" => {
+                    if let Ok(expires) = DateTime::parse_from_rfc2822(value) {
+                        cookie.expires = Some(expires.with_timezone(&Utc));
+                    }
+                }
+                "max-age" => max_age = value.parse::<i64>().ok().or(max_age),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                _ => {}
+            }
+        }
+        if let Some(expires) = max_age
+            .and_then(|seconds: i64| TimeDelta::try_seconds(seconds.max(0)))
+            .and_then(|max_age| Utc::now().checked_add_signed(max_age))
+        {
+            cookie.expires = Some(expires);
+        }
+        if !cookie.matches_domain(default_domain) {
+            return Err(format!(
+                "Set-Cookie 的 Domain {:?} 与来源域名 {default_domain:?} 不匹配",
+                cookie.domain
+            ));
+        }
+        Ok(cookie)
+    }
+
+    /// 发送请求时 `Cookie` 头中的一项，形如 `name=value`
+    pub fn to_header(&self) -> String {
+        format!("{}={}", self.name, self.value)
+    }
+
+    /// 在 `now` 时刻是否已过期
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// 该 Cookie 是否应随发往 `host` 的请求发送
+    ///
+    /// 与 Cookie 域名完全相同，或是其子域名时匹配（忽略大小写与前导 `.`）。
+    pub fn matches_domain(&self, host: &str) -> bool {
+        let domain = self.domain.trim_start_matches('.').to_ascii_lowercase();
+        let host = host.trim_start_matches('.').to_ascii_lowercase();
+        host == domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    }
+}
+
+/// 在多次请求之间保存 Cookie，维持登录状态等会话信息
+#[async_trait]
+pub trait CookieStore: Send + Sync {
+    /// 保存 Cookie；域名、路径与名称都相同的旧 Cookie 会被替换
+    async fn set(&self, cookie: Cookie) -> Result<()>;
+
+    /// 应发往 `domain` 的所有未过期 Cookie，包括父域名上的 Cookie
+    async fn get_all_for_domain(&self, domain: &str) -> Result<Vec<Cookie>>;
+
+    /// 删除指定域名下的同名 Cookie，返回删除的数量
+    async fn remove(&self, domain: &str, name: &str) -> Result<usize>;
+
+    /// 清除所有已过期的 Cookie，返回清除的数量
+    async fn clear_expired(&self) -> Result<usize>;
+
+    /// 清空所有 Cookie
+    async fn clear(&self) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_set_cookie_with_all_attributes() {
+        let cookie = Cookie::parse_set_cookie(
+            "session_id=abc123; Domain=.example.com; Path=/app; \
+             Expires=Wed, 21 Oct 2099 07:28:00 GMT; Secure; HttpOnly; SameSite=Lax",
+            "www.example.com",
+        )
+        .unwrap();
+        assert_eq!(cookie.name, "session_id");
+        assert_eq!(cookie.value, "abc123");
+        assert_eq!(cookie.domain, ".example.com");
+        assert_eq!(cookie.path, "/app");
+        assert_eq!(
+            cookie.expires.unwrap().to_rfc3339(),
+            "2099-10-21T07:28:00+00:00"
+        );
+        assert!(cookie.secure);
+        assert!(cookie.http_only);
+        assert_eq!(cookie.to_header(), "session_id=abc123");
+    }
+
+    #[test]
+    fn set_cookie_defaults_and_max_age_precedence() {
+        let cookie = Cookie::parse_set_cookie(
+            "token=x; Max-Age=60; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            "example.com",
+        )
+        .unwrap();
+        assert_eq!(cookie.domain, "example.com");
+        assert_eq!(cookie.path, "/");
+        assert!(!cookie.secure);
+        let remaining = cookie.expires.unwrap() - Utc::now();
+        assert!(remaining > TimeDelta::seconds(50) && remaining <= TimeDelta::seconds(60));
+
+        assert!(Cookie::parse_set_cookie("no-equals-sign", "example.com").is_err());
+        assert!(Cookie::parse_set_cookie("=value", "example.com").is_err());
+    }
+
+    #[test]
+    fn huge_max_age_is_ignored() {
+        let cookie =
+            Cookie::parse_set_cookie(&format!("token=x; Max-Age={}", i64::MAX), "example.com")
+                .unwrap();
+        assert_eq!(cookie.expires, None);
+
+        let cookie = Cookie::parse_set_cookie(
+            "token=x; Expires=Wed, 21 Oct 2099 07:28:00 GMT; Max-Age=9223372036854775807",
+            "example.com",
+        )
+        .unwrap();
+        assert_eq!(
+            cookie.expires.unwrap().to_rfc3339(),
+            "2099-10-21T07:28:00+00:00"
+        );
+    }
+
+    #[test]
+    fn rejects_domain_not_matching_origin() {
+        assert!(Cookie::parse_set_cookie("sid=1; Domain=evil.com", "www.example.com").is_err());
+        assert!(Cookie::parse_set_cookie("sid=1; Domain=api.example.com", "example.com").is_err());
+
+        let cookie =
+            Cookie::parse_set_cookie("sid=1; Domain=Example.com", "www.example.com").unwrap();
+        assert_eq!(cookie.domain, "Example.com");
+    }
+
+    #[test]
+    fn matches_exact_and_parent_domains() {
+        let cookie = Cookie::new("sid", "1", ".Example.com");
+        assert!(cookie.matches_domain("example.com"));
+        assert!(cookie.matches_domain("api.example.com"));
+        assert!(!cookie.matches_domain("badexample.com"));
+        assert!(!cookie.matches_domain("example.org"));
+
+        let cookie = Cookie::new("sid", "1", "api.example.com");
+        assert!(!cookie.matches_domain("example.com"));
+    }
+}
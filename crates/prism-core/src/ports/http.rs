@@ -0,0 +1,125 @@
+//! HTTP 客户端端口
+
+use async_trait::async_trait;
+
+use crate::domain::Result;
+
+/// HTTP 响应
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// 响应头，按收到的顺序保存；同名的头（如 `Set-Cookie`）各占一项
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// 状态码是否为 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// 第一个同名响应头的值（忽略大小写）
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 以 UTF-8 解码的响应体，非法字节替换为 `U+FFFD`
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// 发送 HTTP 请求
+///
+/// 实现方只需提供 [`HttpClient::request`]，`get`/`post`/`put`/`delete` 都是委托给它的
+/// 默认方法。`headers` 为本次请求额外携带的请求头，通常来自流程合并后的
+/// [`HttpConfig`](crate::domain::HttpConfig)。
+#[async_trait]
+pub trait HttpClient: Send + Sync {
+    /// 以任意方法（如 `"GET"`、`"POST"`）发送请求
+    ///
+    /// 非 2xx 状态码不视为错误，由调用方按 [`HttpResponse::status`] 处理；
+    /// 网络错误、超时等返回 `DomainError::Execution`。
+    async fn request(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(String, String)],
+        body: Option<&[u8]>,
+    ) -> Result<HttpResponse>;
+
+    async fn get(&self, url: &str) -> Result<HttpResponse> {
+        self.request("GET", url, &[], None).await
+    }
+
+    async fn post(&self, url: &str, body: &[u8]) -> Result<HttpResponse> {
+        self.request("POST", url, &[], Some(body)).await
+    }
+
+    async fn put(&self, url: &str, body: &[u8]) -> Result<HttpResponse> {
+        self.request("PUT", url, &[], Some(body)).await
+    }
+
+    async fn delete(&self, url: &str) -> Result<HttpResponse> {
+        self.request("DELETE", url, &[], None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// 一次请求的方法与请求体
+    type Recorded = (String, Option<Vec<u8>>);
+
+    /// 记录收到的请求，并把方法名作为响应体返回
+    #[derive(Default)]
+    struct RecordingClient {
+        requests: Mutex<Vec<Recorded>>,
+    }
+
+    #[async_trait]
+    impl HttpClient for RecordingClient {
+        async fn request(
+            &self,
+            method: &str,
+            _url: &str,
+            _headers: &[(String, String)],
+            body: Option<&[u8]>,
+        ) -> Result<HttpResponse> {
+            self.requests
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .push((method.to_string(), body.map(<[u8]>::to_vec)));
+            Ok(HttpResponse {
+                status: 200,
+                headers: vec![("Content-Type".into(), "text/plain".into())],
+                body: method.as_bytes().to_vec(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn verbs_delegate_to_request() {
+        let recording = RecordingClient::default();
+        let client: &dyn HttpClient = &recording;
+        assert_eq!(client.get("https://a.com").await.unwrap().text(), "GET");
+        client.post("https://a.com", b"x").await.unwrap();
+        client.put("https://a.com", b"y").await.unwrap();
+        let response = client.delete("https://a.com").await.unwrap();
+        assert!(response.is_success());
+        assert_eq!(response.header("content-type"), Some("text/plain"));
+
+        let requests = recording.requests.into_inner().unwrap();
+        let methods: Vec<&str> = requests.iter().map(|(m, _)| m.as_str()).collect();
+        assert_eq!(methods, vec!["GET", "POST", "PUT", "DELETE"]);
+        assert_eq!(requests[1].1.as_deref(), Some(&b"x"[..]));
+        assert_eq!(requests[3].1, None);
+    }
+}
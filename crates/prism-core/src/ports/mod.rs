@@ -0,0 +1,16 @@
+//! 端口（外部依赖的抽象）
+//!
+//! 领域与引擎通过这些 trait 访问脚本引擎、网络、存储等外部能力，
+//! 具体实现位于 [`crate::infra`]。
+
+mod cache;
+mod cookie;
+mod http;
+mod repository;
+mod script;
+
+pub use cache::CacheStore;
+pub use cookie::{Cookie, CookieStore};
+pub use http::{HttpClient, HttpResponse};
+pub use repository::CrawlerRuleRepository;
+pub use script::{ScriptEngine, ScriptEngineType};
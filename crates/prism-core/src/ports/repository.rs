@@ -0,0 +1,61 @@
+//! 规则仓库端口
+
+use async_trait::async_trait;
+
+use crate::domain::{CrawlerRule, FlowType, MediaType, Result};
+
+/// 爬虫规则的持久化存储
+///
+/// 规则的 `id` 由仓库分配；查询结果按 `id` 升序返回。
+#[async_trait]
+pub trait CrawlerRuleRepository: Send + Sync {
+    /// 保存规则并返回其 ID
+    ///
+    /// `id` 为 `None` 时新建并分配 ID，否则更新（或以该 ID 创建）对应规则。
+    async fn save(&self, rule: &CrawlerRule) -> Result<i64>;
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<CrawlerRule>>;
+
+    /// 所有规则，包括已停用的
+    async fn find_all(&self) -> Result<Vec<CrawlerRule>>;
+
+    /// 规则总数，包括已停用的
+    ///
+    /// 默认实现加载全部规则后计数，实现方应尽量覆盖。
+    async fn count(&self) -> Result<usize> {
+        Ok(self.find_all().await?.len())
+    }
+
+    /// 按 `id` 升序跳过 `offset` 条后的至多 `limit` 条规则，用于分页浏览
+    ///
+    /// 默认实现在 [`CrawlerRuleRepository::find_all`] 的结果上切片，实现方应尽量覆盖。
+    async fn find_page(&self, offset: usize, limit: usize) -> Result<Vec<CrawlerRule>> {
+        Ok(self
+            .find_all()
+            .await?
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect())
+    }
+
+    /// 名称完全相同的规则
+    async fn find_by_name(&self, name: &str) -> Result<Vec<CrawlerRule>>;
+
+    async fn find_by_media_type(&self, media_type: MediaType) -> Result<Vec<CrawlerRule>>;
+
+    /// 定义了指定流程的规则
+    async fn find_with_flow_type(&self, flow_type: FlowType) -> Result<Vec<CrawlerRule>>;
+
+    /// 未停用的规则
+    async fn find_enabled(&self) -> Result<Vec<CrawlerRule>>;
+
+    /// 停用规则但保留数据，返回规则是否存在
+    async fn disable(&self, id: i64) -> Result<bool>;
+
+    /// 重新启用规则，返回规则是否存在
+    async fn enable(&self, id: i64) -> Result<bool>;
+
+    /// 删除规则，返回删除前规则是否存在
+    async fn delete(&self, id: i64) -> Result<bool>;
+}
@@ -0,0 +1,49 @@
+//! 脚本引擎端口
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+
+use crate::domain::{DomainError, Result};
+
+/// 支持的脚本引擎
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptEngineType {
+    #[default]
+    Rhai,
+    JavaScript,
+}
+
+/// 执行用户脚本
+#[async_trait]
+pub trait ScriptEngine: Send + Sync {
+    fn engine_type(&self) -> ScriptEngineType;
+
+    /// 执行脚本，`context` 为传入脚本的上下文（通常是 JSON 文本）
+    async fn execute(&self, script: &str, context: &str) -> Result<String>;
+
+    /// 带超时地执行脚本，以结构化 JSON 传入上下文并返回结果
+    ///
+    /// 默认实现把 `context` 序列化为 JSON 文本交给 [`ScriptEngine::execute`]，让它与
+    /// `tokio::time::sleep` 竞争，超时后返回 `DomainError::Execution("script timeout")`；
+    /// 结果能解析为 JSON 时按 JSON 返回，否则作为字符串。用户脚本可能死循环，
+    /// 执行不可信脚本时应始终使用本方法。若 `execute` 在阻塞线程中运行，
+    /// 超时只能停止等待，实现方应另行限制脚本的运行量。
+    async fn execute_with_timeout(
+        &self,
+        script: &str,
+        context: &Value,
+        timeout: Duration,
+    ) -> Result<Value> {
+        let context = context.to_string();
+        let output = tokio::select! {
+            result = self.execute(script, &context) => result?,
+            _ = tokio::time::sleep(timeout) => return Err(DomainError::Execution("script timeout".to_string())),
+        };
+        Ok(serde_json::from_str(&output).unwrap_or(Value::String(output)))
+    }
+}
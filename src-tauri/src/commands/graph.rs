@@ -0,0 +1,142 @@
+//! 节点图相关命令
+
+use prism_core::domain::{Connection, ErrorPayload, Flow, NodeGraph};
+
+/// 校验一条待添加的连接，用于编辑器拖拽连线时的实时反馈
+///
+/// 只检查这一条连接，不校验整张图，也不修改图。
+#[tauri::command]
+#[specta::specta]
+pub fn validate_connection(graph: NodeGraph, connection: Connection) -> Result<(), ErrorPayload> {
+    connection.validate_against(&graph).map_err(Into::into)
+}
+
+/// 流程的执行顺序预览：按依赖层级分组的节点 ID，同一层的节点可以并发执行
+///
+/// 先校验节点图，失败时返回第一个错误；存在环时错误码为
+/// `validation.cycle_detected`，`details.path` 为环上的节点，供编辑器高亮。
+#[tauri::command]
+#[specta::specta]
+pub fn flow_execution_order(flow: Flow) -> Result<Vec<Vec<String>>, ErrorPayload> {
+    flow.graph.validate()?;
+    Ok(flow.graph.execution_layers()?)
+}
+
+/// 为新建的节点分配 ID，以节点类型为前缀，保证不与图中已有节点冲突
+///
+/// 规则见 [`NodeGraph::next_node_id`]，编辑器添加节点前应先调用本命令。
+#[tauri::command]
+#[specta::specta]
+pub fn allocate_node_id(graph: NodeGraph, type_id: String) -> String {
+    graph.next_node_id(&type_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prism_core::domain::{DataType, FlowType, Node, Port};
+
+    fn graph() -> NodeGraph {
+        let mut graph = NodeGraph::new();
+        graph
+            .add_node(Node::new("a", "constant").with_output(Port::new(
+                "value",
+                "值",
+                DataType::Boolean,
+            )))
+            .unwrap();
+        graph
+            .add_node(Node::new("b", "css_selector").with_input(Port::new(
+                "html",
+                "HTML",
+                DataType::String,
+            )))
+            .unwrap();
+        graph
+    }
+
+    #[test]
+    fn type_mismatch_returns_structured_error() {
+        let err =
+            validate_connection(graph(), Connection::new("a", "value", "b", "html")).unwrap_err();
+        assert_eq!(err.code, "validation.type_mismatch");
+        assert!(!err.message.is_empty());
+    }
+
+    #[test]
+    fn valid_connection_passes() {
+        let mut graph = graph();
+        graph
+            .add_node(Node::new("c", "set_variable").with_input(Port::new(
+                "value",
+                "值",
+                DataType::Any,
+            )))
+            .unwrap();
+        assert!(validate_connection(graph, Connection::new("a", "value", "c", "value")).is_ok());
+    }
+
+    fn any_node(id: &str, input: bool, output: bool) -> Node {
+        let mut node = Node::new(id, "constant");
+        if input {
+            node = node.with_input(Port::new("in", "in", DataType::Any).multiple());
+        }
+        if output {
+            node = node.with_output(Port::new("out", "out", DataType::Any));
+        }
+        node
+    }
+
+    fn flow(nodes: Vec<Node>, edges: &[(&str, &str)]) -> Flow {
+        let mut graph = NodeGraph::new();
+        for node in nodes {
+            graph.add_node(node).unwrap();
+        }
+        for (from, to) in edges {
+            graph
+                .add_connection(Connection::new(*from, "out", *to, "in"))
+                .unwrap();
+        }
+        Flow::new(FlowType::Detail, graph)
+    }
+
+    #[test]
+    fn execution_order_groups_diamond_branches() {
+        let diamond = flow(
+            vec![
+                any_node("a", false, true),
+                any_node("b", true, true),
+                any_node("c", true, true),
+                any_node("d", true, false),
+            ],
+            &[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")],
+        );
+        assert_eq!(
+            flow_execution_order(diamond).unwrap(),
+            vec![vec!["a"], vec!["b", "c"], vec!["d"]]
+        );
+    }
+
+    #[test]
+    fn execution_order_reports_cycle_path() {
+        let cyclic = flow(
+            vec![any_node("a", true, true), any_node("b", true, true)],
+            &[("a", "b"), ("b", "a")],
+        );
+        let err = flow_execution_order(cyclic).unwrap_err();
+        assert_eq!(err.code, "validation.cycle_detected");
+        assert_eq!(
+            err.details.unwrap()["path"],
+            serde_json::json!(["a", "b", "a"])
+        );
+    }
+
+    #[test]
+    fn allocated_id_does_not_collide() {
+        let mut graph = graph();
+        graph.add_node(Node::new("constant_1", "constant")).unwrap();
+        let id = allocate_node_id(graph.clone(), "constant".into());
+        assert_eq!(id, "constant_2");
+        assert!(graph.add_node(Node::new(id, "constant")).is_ok());
+    }
+}
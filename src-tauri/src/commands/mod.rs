@@ -0,0 +1,5 @@
+//! Tauri 命令
+
+pub mod graph;
+pub mod registry;
+pub mod rule;
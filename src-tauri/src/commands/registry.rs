@@ -0,0 +1,48 @@
+//! 节点类型注册表相关命令
+
+use prism_core::domain::{NodeCategory, NodeTypeMetadata, NodeTypeRegistry};
+use tauri::State;
+
+/// 节点面板中的节点类型，可按分类过滤
+///
+/// 结果按分类的面板顺序、再按 `type_id` 排序。
+#[tauri::command]
+#[specta::specta]
+pub fn list_node_types(
+    registry: State<'_, NodeTypeRegistry>,
+    category: Option<NodeCategory>,
+) -> Vec<NodeTypeMetadata> {
+    node_types(&registry, category)
+}
+
+fn node_types(
+    registry: &NodeTypeRegistry,
+    category: Option<NodeCategory>,
+) -> Vec<NodeTypeMetadata> {
+    let mut types: Vec<NodeTypeMetadata> = registry
+        .all()
+        .into_iter()
+        .filter(|t| category.is_none_or(|c| t.category == c))
+        .cloned()
+        .collect();
+    types.sort_by(|a, b| (a.category, &a.type_id).cmp(&(b.category, &b.type_id)));
+    types
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_all_or_filtered_types() {
+        let registry = NodeTypeRegistry::with_builtins();
+        let all = node_types(&registry, None);
+        assert_eq!(all.len(), registry.len());
+        assert_eq!(all[0].category, NodeCategory::DataSource);
+
+        let sources = node_types(&registry, Some(NodeCategory::DataSource));
+        let ids: Vec<&str> = sources.iter().map(|t| t.type_id.as_str()).collect();
+        assert_eq!(ids, vec!["constant", "http_request"]);
+        assert!(node_types(&registry, Some(NodeCategory::Transform)).is_empty());
+    }
+}
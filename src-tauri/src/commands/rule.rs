@@ -0,0 +1,94 @@
+//! 规则相关命令
+
+use prism_core::domain::{CrawlerRule, ErrorPayload};
+use serde_json::Value;
+
+/// 校验整条规则，一次返回所有错误，供编辑器保存前集中提示
+///
+/// 依次检查必需流程是否齐全（每个缺少或类型不符的流程各报告一项），以及每个
+/// 已定义流程的节点图（结果与
+/// [`NodeGraph::validate_all`](prism_core::domain::NodeGraph::validate_all) 一致，
+/// 只保留错误级别的问题）和出口节点。节点图上的错误在 `details.flow_type`
+/// 中注明所属流程。
+#[tauri::command]
+#[specta::specta]
+pub fn validate_rule(rule: CrawlerRule) -> Result<(), Vec<ErrorPayload>> {
+    let mut errors: Vec<ErrorPayload> = rule
+        .flows
+        .validation_errors()
+        .into_iter()
+        .map(ErrorPayload::from)
+        .collect();
+    for (flow_type, flow) in rule.flows.defined_flows() {
+        let before = errors.len();
+        for finding in flow.graph.validate_all() {
+            if !finding.is_error() {
+                continue;
+            }
+            let mut payload = ErrorPayload::from(finding.error);
+            if let Some(Value::Object(details)) = &mut payload.details {
+                details.insert("flow_type".into(), flow_type.as_str().into());
+            }
+            errors.push(payload);
+        }
+        if errors.len() == before {
+            if let Err(e) = flow.validate() {
+                errors.push(e.into());
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prism_core::domain::{
+        Connection, DataType, Flow, FlowType, MediaType, Node, NodeGraph, Port,
+    };
+
+    #[test]
+    fn missing_required_flows_are_reported() {
+        let errors = validate_rule(CrawlerRule::new("空规则", MediaType::Video)).unwrap_err();
+        // 视频规则缺少搜索、详情、内容三个必需流程
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().all(|e| e.code == "invalid_rule"));
+    }
+
+    #[test]
+    fn graph_errors_carry_flow_type() {
+        let mut graph = NodeGraph::new();
+        graph
+            .add_node(Node::new("a", "constant").with_output(Port::new(
+                "value",
+                "值",
+                DataType::String,
+            )))
+            .unwrap();
+        graph
+            .add_node(Node::new("b", "set_variable").with_input(Port::new(
+                "value",
+                "值",
+                DataType::Number,
+            )))
+            .unwrap();
+        graph
+            .add_connection(Connection::new("a", "value", "b", "value"))
+            .unwrap();
+
+        let mut rule = CrawlerRule::new("规则", MediaType::Video);
+        rule.flows
+            .insert(FlowType::Search, Flow::new(FlowType::Search, graph));
+        let errors = validate_rule(rule).unwrap_err();
+        let mismatch = errors
+            .iter()
+            .find(|e| e.code == "validation.type_mismatch")
+            .unwrap();
+        assert_eq!(mismatch.details.as_ref().unwrap()["flow_type"], "search");
+    }
+}
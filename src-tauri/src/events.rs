@@ -0,0 +1,77 @@
+//! 推送给前端的事件
+
+use prism_core::domain::FlowType;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Runtime};
+use tauri_specta::Event;
+
+/// 规则执行进度：每完成一个节点推送一次，供前端显示进度条
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type, Event)]
+pub struct ExecutionProgress {
+    pub flow_type: FlowType,
+    /// 刚完成的节点
+    pub node_id: String,
+    /// 已完成的节点数
+    pub completed: usize,
+    /// 本流程要执行的节点总数
+    pub total: usize,
+}
+
+impl ExecutionProgress {
+    /// `completed` 超过 `total` 时按 `total` 计
+    pub fn new(
+        flow_type: FlowType,
+        node_id: impl Into<String>,
+        completed: usize,
+        total: usize,
+    ) -> Self {
+        Self {
+            flow_type,
+            node_id: node_id.into(),
+            completed: completed.min(total),
+            total,
+        }
+    }
+}
+
+/// 单个流程执行结束
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type, Event)]
+pub struct FlowCompleted {
+    pub flow_type: FlowType,
+    pub success: bool,
+}
+
+/// 推送一次执行进度
+pub fn emit_progress<R: Runtime>(
+    app: &AppHandle<R>,
+    flow_type: FlowType,
+    node_id: impl Into<String>,
+    completed: usize,
+    total: usize,
+) -> tauri::Result<()> {
+    ExecutionProgress::new(flow_type, node_id, completed, total).emit(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn progress_payload_serializes_for_frontend() {
+        let progress = ExecutionProgress::new(FlowType::Search, "fetch", 5, 3);
+        assert_eq!(
+            serde_json::to_value(&progress).unwrap(),
+            json!({ "flow_type": "search", "node_id": "fetch", "completed": 3, "total": 3 })
+        );
+        let completed = FlowCompleted {
+            flow_type: FlowType::Search,
+            success: true,
+        };
+        assert_eq!(
+            serde_json::to_value(&completed).unwrap(),
+            json!({ "flow_type": "search", "success": true })
+        );
+    }
+}
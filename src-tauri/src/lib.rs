@@ -0,0 +1,71 @@
+//! Prism 桌面应用
+//!
+//! 通过 Tauri 命令把 `prism-core` 的能力暴露给前端，命令签名由 tauri-specta
+//! 导出为 TypeScript 绑定。
+
+pub mod commands;
+pub mod events;
+
+use prism_core::domain::NodeTypeRegistry;
+use specta_typescript::{BigIntExportBehavior, Typescript};
+use tauri::Manager;
+use tauri_specta::{collect_commands, collect_events, Builder};
+
+/// 注册所有命令与事件的 specta 构建器
+pub fn specta_builder() -> Builder<tauri::Wry> {
+    Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            commands::graph::validate_connection,
+            commands::graph::flow_execution_order,
+            commands::graph::allocate_node_id,
+            commands::registry::list_node_types,
+            commands::rule::validate_rule,
+        ])
+        .events(collect_events![
+            events::ExecutionProgress,
+            events::FlowCompleted,
+        ])
+}
+
+/// TypeScript 导出配置；计数与毫秒等整数字段在前端按 `number` 处理
+fn typescript() -> Typescript {
+    Typescript::default().bigint(BigIntExportBehavior::Number)
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    let builder = specta_builder();
+
+    #[cfg(debug_assertions)]
+    builder
+        .export(typescript(), "../src/bindings.ts")
+        .expect("导出 TypeScript 绑定失败");
+
+    tauri::Builder::default()
+        .invoke_handler(builder.invoke_handler())
+        .setup(move |app| {
+            builder.mount_events(app);
+            app.manage(NodeTypeRegistry::with_builtins());
+            Ok(())
+        })
+        .run(tauri::generate_context!())
+        .expect("启动 Tauri 应用失败");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_typescript_bindings() {
+        let path = std::env::temp_dir().join("prism-bindings-test.ts");
+        specta_builder().export(typescript(), &path).unwrap();
+        let bindings = std::fs::read_to_string(&path).unwrap();
+        assert!(bindings.contains("validateConnection"));
+        assert!(bindings.contains("validateRule"));
+        assert!(bindings.contains("listNodeTypes"));
+        assert!(bindings.contains("flowExecutionOrder"));
+        assert!(bindings.contains("allocateNodeId"));
+        assert!(bindings.contains("executionProgress"));
+    }
+}
@@ -0,0 +1,6 @@
+// 在 Windows 发布版本中隐藏额外的控制台窗口，请勿删除
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+fn main() {
+    prism_lib::run()
+}